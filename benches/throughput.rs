@@ -0,0 +1,77 @@
+//! Throughput of `check` and `fix` in MB/s. Run with `cargo bench`.
+
+use std::time::{Duration, Instant};
+
+use editorconfig_lint::{check, fix, Charset, Config, IndentSize, IndentStyle, LineEnding};
+
+const INPUT_SIZE: usize = 8 * 1024 * 1024;
+const MIN_DURATION: Duration = Duration::from_secs(1);
+
+fn generate(text: &str, charset: Option<Charset>) -> Vec<u8> {
+    let line = match charset {
+        Some(Charset::Utf16LittleEndian) => {
+            text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+        }
+        Some(Charset::Utf16BigEndian) => text.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+        _ => text.as_bytes().to_vec(),
+    };
+    line.iter()
+        .copied()
+        .cycle()
+        .take(INPUT_SIZE / line.len() * line.len())
+        .collect()
+}
+
+fn measure(name: &str, len: usize, mut f: impl FnMut()) {
+    let begin = Instant::now();
+    let mut iterations = 0;
+    while begin.elapsed() < MIN_DURATION {
+        f();
+        iterations += 1;
+    }
+    let seconds = begin.elapsed().as_secs_f64() / iterations as f64;
+    println!(
+        "{name:<40} {:>10.2} MB/s",
+        len as f64 / seconds / 1024.0 / 1024.0
+    );
+}
+
+fn main() {
+    let ascii = "    let value = some_function(argument, another_argument); // comment\n";
+    let multibyte = "    let 값 = \"가나다라마바사 こんにちは 😀\";\n";
+    let cases = [
+        ("ascii", ascii, None),
+        ("ascii utf-8", ascii, Some(Charset::Utf8)),
+        ("ascii latin1", ascii, Some(Charset::Latin1)),
+        ("multibyte utf-8", multibyte, Some(Charset::Utf8)),
+        (
+            "multibyte utf-16le",
+            multibyte,
+            Some(Charset::Utf16LittleEndian),
+        ),
+        (
+            "multibyte utf-16be",
+            multibyte,
+            Some(Charset::Utf16BigEndian),
+        ),
+    ];
+
+    for (name, text, charset) in cases {
+        let input = generate(text, charset);
+        let config = Config {
+            charset,
+            indent_style: Some(IndentStyle::Space),
+            indent_size: Some(IndentSize::Columns(4)),
+            end_of_line: Some(LineEnding::Lf),
+            trim_trailing_whitespace: Some(true),
+            insert_final_newline: Some(true),
+            ..Default::default()
+        };
+        measure(&format!("check {name}"), input.len(), || {
+            check(&input[..], config.clone()).unwrap();
+        });
+        measure(&format!("fix {name}"), input.len(), || {
+            fix(&input[..], Vec::with_capacity(input.len()), &config).unwrap();
+        });
+    }
+}
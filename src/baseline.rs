@@ -0,0 +1,209 @@
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{
+    reader::{read_lines, Character, IndentChar},
+    Charset, Diagnosis,
+};
+
+const BASELINE_VERSION: u32 = 1;
+
+/// Known diagnosis which is not reported
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BaselineEntry {
+    /// [`crate::Reason::code`]
+    pub reason: String,
+    /// Hash of the line content without line ending, so that the entry survives moved lines
+    pub fingerprint: String,
+}
+
+/// Diagnoses recorded by `check --write-baseline`, keyed by file.
+/// Files are relative to the directory of the baseline file,
+/// so that it works from any working directory.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct Baseline {
+    version: u32,
+    files: BTreeMap<String, Vec<BaselineEntry>>,
+    /// canonicalized directory which files are relative to
+    #[serde(skip)]
+    root: PathBuf,
+    /// entries of checked files which did not match any diagnosis
+    #[serde(skip)]
+    stale: Vec<(String, BaselineEntry)>,
+}
+
+/// Canonicalized directory of the baseline file at `path`
+fn baseline_root(path: &Path) -> std::io::Result<PathBuf> {
+    match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.canonicalize(),
+        _ => std::env::current_dir()?.canonicalize(),
+    }
+}
+
+/// 64-bit FNV-1a, which is stable between builds unlike `std::hash`
+fn fnv1a(bytes: impl Iterator<Item = u8>) -> u64 {
+    bytes.fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn fingerprint(line: &[Character]) -> String {
+    let bytes = line.iter().flat_map(|ch| match ch {
+        Character::Valid(bytes) | Character::Invalid(bytes) | Character::Control(bytes) => {
+            bytes.as_slice().to_vec()
+        }
+        Character::Indent(IndentChar::Space) => vec![b' '],
+        Character::Indent(IndentChar::Tab) => vec![b'\t'],
+        Character::Bom | Character::ReversedBom => "\u{FEFF}".as_bytes().to_vec(),
+        Character::NewLine(_) => Vec::new(),
+    });
+    format!("{:016x}", fnv1a(bytes))
+}
+
+/// Entries of `diagnoses` in `source`
+fn entries(
+    source: &[u8],
+    charset: Option<Charset>,
+    diagnoses: &[Diagnosis],
+) -> std::io::Result<Vec<BaselineEntry>> {
+    if diagnoses.is_empty() {
+        return Ok(Vec::new());
+    }
+    let lines = read_lines(source, charset)?;
+    Ok(diagnoses
+        .iter()
+        .map(|diagnosis| BaselineEntry {
+            reason: diagnosis.reason.code().to_string(),
+            fingerprint: fingerprint(
+                lines
+                    .get(diagnosis.line - 1)
+                    .map(Vec::as_slice)
+                    .unwrap_or_default(),
+            ),
+        })
+        .collect())
+}
+
+impl Baseline {
+    /// Empty baseline which is saved to `path` later
+    pub fn new(path: &Path) -> std::io::Result<Baseline> {
+        Ok(Baseline {
+            version: BASELINE_VERSION,
+            files: BTreeMap::new(),
+            root: baseline_root(path)?,
+            stale: Vec::new(),
+        })
+    }
+
+    pub fn open(path: &Path) -> std::io::Result<Baseline> {
+        let mut baseline: Baseline = serde_json::from_slice(&std::fs::read(path)?)?;
+        baseline.root = baseline_root(path)?;
+        if baseline.version != BASELINE_VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("unsupported baseline version {}", baseline.version),
+            ));
+        }
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        std::fs::write(path, json)
+    }
+
+    /// `/` separated path of `file` relative to the root if it is under the root
+    fn key(&self, file: &Path) -> String {
+        let file = file
+            .canonicalize()
+            .or_else(|_| std::path::absolute(file))
+            .unwrap_or_else(|_| file.to_path_buf());
+        match file.strip_prefix(&self.root) {
+            Ok(relative) => relative
+                .components()
+                .filter_map(|component| match component {
+                    Component::Normal(name) => Some(name.to_string_lossy()),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("/"),
+            Err(_) => file.to_string_lossy().to_string(),
+        }
+    }
+
+    /// Number of recorded diagnoses
+    pub fn len(&self) -> usize {
+        self.files.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Record `diagnoses` of `file` whose content is `source`
+    pub fn add(
+        &mut self,
+        file: &Path,
+        source: &[u8],
+        charset: Option<Charset>,
+        diagnoses: &[Diagnosis],
+    ) -> std::io::Result<()> {
+        let entries = entries(source, charset, diagnoses)?;
+        if !entries.is_empty() {
+            self.files
+                .entry(self.key(file))
+                .or_default()
+                .extend(entries);
+        }
+        Ok(())
+    }
+
+    /// Remove recorded diagnoses of `file` from `diagnoses`.
+    /// `source` is only read if there is any diagnosis.
+    /// Each file should be passed once. Entries which match nothing become stale.
+    pub fn suppress(
+        &mut self,
+        file: &Path,
+        source: &[u8],
+        charset: Option<Charset>,
+        diagnoses: &mut Vec<Diagnosis>,
+    ) -> std::io::Result<()> {
+        let file = self.key(file);
+        let Some(mut known) = self.files.remove(&file) else {
+            return Ok(());
+        };
+        let entries = entries(source, charset, diagnoses)?;
+        let mut entries = entries.iter();
+        diagnoses.retain(|_| {
+            let entry = entries.next().unwrap();
+            match known.iter().position(|known| known == entry) {
+                Some(i) => {
+                    known.swap_remove(i);
+                    false
+                }
+                None => true,
+            }
+        });
+        self.stale
+            .extend(known.into_iter().map(|entry| (file.clone(), entry)));
+        Ok(())
+    }
+
+    /// Entries which no longer match, of checked files and files which do not exist
+    pub fn stale(&self) -> Vec<(&str, &BaselineEntry)> {
+        let mut stale: Vec<_> = self
+            .stale
+            .iter()
+            .map(|(file, entry)| (file.as_str(), entry))
+            .collect();
+        for (file, entries) in &self.files {
+            if !self.root.join(file).exists() {
+                stale.extend(entries.iter().map(|entry| (file.as_str(), entry)));
+            }
+        }
+        stale
+    }
+}
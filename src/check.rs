@@ -1,20 +1,171 @@
+use unicode_width::UnicodeWidthChar;
+
 use crate::{
-    reader::{Character, CharacterReader, IndentChar, NewLineChar, Reader},
+    reader::{decode, CharByteArray, Character, CharacterReader, IndentChar, NewLineChar, Reader},
     Charset, Config, IndentStyle, LineEnding,
 };
 
-#[derive(Debug)]
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "code", rename_all = "kebab-case")]
 pub enum Reason {
-    IndentStyle,
-    IndentSizeMismatch(usize),
-    EndOfLineMismatch,
+    IndentStyle {
+        expected: IndentStyle,
+    },
+    IndentSizeMismatch {
+        expected: usize,
+        actual: usize,
+    },
+    EndOfLineMismatch {
+        expected: LineEnding,
+        actual: LineEnding,
+    },
     TrailingWhiteSpaces,
     NoFinalNewline,
+    SuperfluousFinalNewline,
     BomNotFound,
+    BomMismatch,
     InvalidCharacter,
+    ControlCharacter {
+        code_point: u32,
+    },
+    LineTooLong {
+        expected: usize,
+        actual: usize,
+    },
+}
+
+/// Static description of a [`Reason`] variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rule {
+    /// Stable identifier of the reason
+    pub code: &'static str,
+    /// EditorConfig property which triggers the reason
+    pub property: &'static str,
+    pub description: &'static str,
+}
+
+/// Every rule, in the order of [`Reason`] variants
+pub const RULES: [Rule; 11] = [
+    Rule {
+        code: "indent-style",
+        property: "indent_style",
+        description: "Indentation uses a character other than indent_style",
+    },
+    Rule {
+        code: "indent-size-mismatch",
+        property: "indent_size",
+        description: "Indentation width is not a multiple of indent_size",
+    },
+    Rule {
+        code: "end-of-line-mismatch",
+        property: "end_of_line",
+        description: "Line ending differs from end_of_line",
+    },
+    Rule {
+        code: "trailing-white-spaces",
+        property: "trim_trailing_whitespace",
+        description: "Line ends with whitespace characters",
+    },
+    Rule {
+        code: "no-final-newline",
+        property: "insert_final_newline",
+        description: "File does not end with a newline",
+    },
+    Rule {
+        code: "superfluous-final-newline",
+        property: "insert_final_newline",
+        description: "File ends with a newline",
+    },
+    Rule {
+        code: "bom-not-found",
+        property: "charset",
+        description: "File does not start with a byte order mark",
+    },
+    Rule {
+        code: "bom-mismatch",
+        property: "charset",
+        description: "Byte order mark is for the other byte order of charset",
+    },
+    Rule {
+        code: "invalid-character",
+        property: "charset",
+        description: "File contains a character which is invalid for charset",
+    },
+    Rule {
+        code: "control-character",
+        property: "charset",
+        description: "File contains a control character which is not printable in charset",
+    },
+    Rule {
+        code: "line-too-long",
+        property: "max_line_length",
+        description: "Line is wider than max_line_length",
+    },
+];
+
+impl Reason {
+    pub fn rule(&self) -> &'static Rule {
+        &RULES[match self {
+            Reason::IndentStyle { .. } => 0,
+            Reason::IndentSizeMismatch { .. } => 1,
+            Reason::EndOfLineMismatch { .. } => 2,
+            Reason::TrailingWhiteSpaces => 3,
+            Reason::NoFinalNewline => 4,
+            Reason::SuperfluousFinalNewline => 5,
+            Reason::BomNotFound => 6,
+            Reason::BomMismatch => 7,
+            Reason::InvalidCharacter => 8,
+            Reason::ControlCharacter { .. } => 9,
+            Reason::LineTooLong { .. } => 10,
+        }]
+    }
+
+    /// Stable identifier of the reason
+    pub fn code(&self) -> &'static str {
+        self.rule().code
+    }
+
+    /// Whether the reason is about the whole file rather than the line it is reported at
+    pub fn is_file_level(&self) -> bool {
+        matches!(
+            self,
+            Reason::NoFinalNewline
+                | Reason::SuperfluousFinalNewline
+                | Reason::BomNotFound
+                | Reason::BomMismatch
+        )
+    }
 }
 
-#[derive(Debug)]
+impl std::fmt::Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reason::IndentStyle { expected } => write!(f, "indentation should use {expected}"),
+            Reason::IndentSizeMismatch { expected, actual } => write!(
+                f,
+                "indentation width {actual} is not a multiple of {expected}"
+            ),
+            Reason::EndOfLineMismatch { expected, actual } => {
+                write!(f, "expected {expected} line ending, found {actual}")
+            }
+            Reason::TrailingWhiteSpaces => write!(f, "trailing whitespace"),
+            Reason::NoFinalNewline => write!(f, "missing newline at end of file"),
+            Reason::SuperfluousFinalNewline => write!(f, "unexpected newline at end of file"),
+            Reason::BomNotFound => write!(f, "missing byte order mark"),
+            Reason::BomMismatch => write!(f, "byte order mark does not match the charset"),
+            Reason::InvalidCharacter => write!(f, "invalid character for the charset"),
+            Reason::ControlCharacter { code_point } => {
+                write!(f, "control character U+{code_point:04X}")
+            }
+            Reason::LineTooLong { expected, actual } => {
+                write!(f, "line width {actual} exceeds max_line_length {expected}")
+            }
+        }
+    }
+}
+
+/// `range` is the 1-based column range of `line`, end exclusive.
+#[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct Diagnosis {
     pub line: usize,
     pub range: (usize, usize),
@@ -29,7 +180,7 @@ impl Diagnosis {
     ) -> std::io::Result<()> {
         write!(
             out,
-            "error: {:?} at {}:{}:",
+            "error: {} at {}:{}:",
             self.reason, file_name, self.line
         )?;
         if self.range.0 == self.range.1 {
@@ -42,9 +193,16 @@ impl Diagnosis {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum State {
-    Indent { len: usize, style_error: bool },
+    /// `len` characters which are `width` columns wide
+    Indent {
+        len: usize,
+        width: usize,
+        style_error: bool,
+    },
     NonWhitespace,
-    NonIndentWhitespace { len: usize },
+    NonIndentWhitespace {
+        len: usize,
+    },
 }
 
 struct CheckState<'a> {
@@ -52,6 +210,14 @@ struct CheckState<'a> {
     col: usize,
     state: State,
     prev_newline: Option<NewLineChar>,
+    /// line and range of the last newline if nothing but newlines follows it
+    last_newline: Option<(usize, (usize, usize))>,
+    /// display width of current line
+    width: usize,
+    /// first column which exceeds `max_line_length`
+    too_long_from: Option<usize>,
+    /// incomplete UTF-8 sequence of unchecked encoding
+    utf8_sequence: Vec<u8>,
     diagnosis: Vec<Diagnosis>,
     config: &'a Config,
 }
@@ -66,97 +232,223 @@ impl<'a> CheckState<'a> {
         self.col = 1;
     }
 
-    fn check_end_of_newline(&mut self) {
-        if let Some(new_line) = self.prev_newline.take() {
-            if self
-                .config
-                .end_of_line
-                .map(|v| new_line != v)
-                .unwrap_or(false)
-            {
+    /// Check newline which starts at current column
+    fn check_newline(&mut self, actual: LineEnding) {
+        if let Some(expected) = self.config.end_of_line {
+            if expected != actual {
+                let len = if actual == LineEnding::Crlf { 2 } else { 1 };
                 self.push_diagnosis(Diagnosis {
                     line: self.line,
-                    range: (self.col - 1, self.col),
-                    reason: Reason::EndOfLineMismatch,
+                    range: (self.col, self.col + len),
+                    reason: Reason::EndOfLineMismatch { expected, actual },
                 })
             }
+        }
+    }
+
+    fn check_end_of_newline(&mut self) {
+        if let Some(new_line) = self.prev_newline.take() {
+            self.check_newline(new_line.into());
             self.move_next_line();
         }
     }
 
-    fn check_end_of_indent(&mut self, len: usize, style_error: bool) {
+    /// Add width of a character at current column
+    fn add_width(&mut self, width: usize) {
+        self.width += width;
+        if let Some(max) = self.config.max_line_length {
+            if self.width > max && self.too_long_from.is_none() {
+                self.too_long_from = Some(self.col);
+            }
+        }
+    }
+
+    /// Add `len` printable ASCII characters which start at current column
+    fn add_ascii_run(&mut self, len: usize) {
+        if let Some(max) = self.config.max_line_length {
+            if self.width + len > max && self.too_long_from.is_none() {
+                self.too_long_from = Some(self.col + max.saturating_sub(self.width));
+            }
+        }
+        self.width += len;
+        self.col += len;
+    }
+
+    fn char_width(&mut self, bytes: &CharByteArray) -> usize {
+        if self.config.charset.is_some() {
+            return decode(bytes.as_slice(), self.config.charset)
+                .width()
+                .unwrap_or(0);
+        }
+
+        // each byte is a character of unchecked encoding. measure UTF-8 sequences at their last byte
+        let byte = bytes.buffer[0];
+        if byte < 0x80 {
+            return 1 + std::mem::take(&mut self.utf8_sequence).len();
+        }
+        self.utf8_sequence.push(byte);
+        match std::str::from_utf8(&self.utf8_sequence) {
+            Ok(s) => {
+                let width = s.chars().next().and_then(|ch| ch.width()).unwrap_or(0);
+                self.utf8_sequence.clear();
+                width
+            }
+            Err(e) if e.error_len().is_none() => 0,
+            Err(_) => std::mem::take(&mut self.utf8_sequence).len(),
+        }
+    }
+
+    fn check_line_length(&mut self) {
+        if let (Some(from), Some(expected)) = (self.too_long_from, self.config.max_line_length) {
+            self.push_diagnosis(Diagnosis {
+                line: self.line,
+                range: (from, self.col),
+                reason: Reason::LineTooLong {
+                    expected,
+                    actual: self.width,
+                },
+            });
+        }
+        self.width = 0;
+        self.too_long_from = None;
+        self.utf8_sequence.clear();
+    }
+
+    fn indent_style_reason(&self) -> Reason {
+        Reason::IndentStyle {
+            expected: self
+                .config
+                .indent_style
+                .expect("style error is only detected with indent_style"),
+        }
+    }
+
+    fn check_end_of_indent(&mut self, len: usize, width: usize, style_error: bool) {
         if style_error {
             self.push_diagnosis(Diagnosis {
                 line: self.line,
                 range: (self.col - len, self.col),
-                reason: Reason::IndentStyle,
+                reason: self.indent_style_reason(),
             });
-        } else if let Some(size) = self.config.indent_size {
-            if len % size != 0 {
+        } else if let Some(size) = self.config.indent_width() {
+            if !width.is_multiple_of(size) {
+                self.push_diagnosis(Diagnosis {
+                    line: self.line,
+                    range: (self.col - len, self.col),
+                    reason: Reason::IndentSizeMismatch {
+                        expected: size,
+                        actual: width,
+                    },
+                });
+            }
+        }
+    }
+
+    fn check_trailing_whitespaces(&mut self) {
+        let trailing = match self.state {
+            State::NonIndentWhitespace { len } => Some(len),
+            State::Indent {
+                len, style_error, ..
+            } => {
+                if style_error {
+                    self.push_diagnosis(Diagnosis {
+                        line: self.line,
+                        range: (self.col - len, self.col),
+                        reason: self.indent_style_reason(),
+                    });
+                }
+                Some(len)
+            }
+            _ => None,
+        };
+        if let Some(len) = trailing {
+            if len != 0 && self.config.trim_trailing_whitespace.unwrap_or(false) {
                 self.push_diagnosis(Diagnosis {
                     line: self.line,
                     range: (self.col - len, self.col),
-                    reason: Reason::IndentSizeMismatch(len),
+                    reason: Reason::TrailingWhiteSpaces,
                 });
             }
         }
     }
 
+    fn check_end_of_input(&mut self) {
+        let is_empty = self.line == 1 && self.col == 1 && self.last_newline.is_none();
+        self.check_trailing_whitespaces();
+        self.check_line_length();
+        self.check_end_of_newline();
+
+        match (self.config.insert_final_newline, self.last_newline) {
+            (Some(true), None) if !is_empty => self.push_diagnosis(Diagnosis {
+                line: self.line,
+                range: (self.col, self.col),
+                reason: Reason::NoFinalNewline,
+            }),
+            (Some(false), Some((line, range))) => self.push_diagnosis(Diagnosis {
+                line,
+                range,
+                reason: Reason::SuperfluousFinalNewline,
+            }),
+            _ => {}
+        }
+    }
+
     fn check_ch(&mut self, ch: Character) {
         match ch {
             Character::Indent(indent) => {
                 match self.state {
                     State::NonWhitespace => self.state = State::NonIndentWhitespace { len: 1 },
-                    State::Indent { len, style_error } => {
+                    State::NonIndentWhitespace { len } => {
+                        self.state = State::NonIndentWhitespace { len: len + 1 }
+                    }
+                    State::Indent {
+                        len,
+                        width,
+                        style_error,
+                    } => {
                         if len == 0 {
                             self.check_end_of_newline();
                         }
-                        self.state = match (indent, self.config.indent_style) {
-                            (IndentChar::Space, Some(IndentStyle::Tab)) => State::Indent {
-                                len: len + 1,
-                                style_error: true,
-                            },
-                            (IndentChar::Tab, Some(IndentStyle::Space)) => State::Indent {
-                                len: len + 1,
-                                style_error: true,
-                            },
-                            (_, _) => State::Indent {
-                                len: len + 1,
-                                style_error,
-                            },
-                        }
+                        let width = match indent {
+                            IndentChar::Space => width + 1,
+                            IndentChar::Tab => {
+                                let tab_width = self.config.tab_width_or_default();
+                                (width / tab_width + 1) * tab_width
+                            }
+                        };
+                        let style_error = style_error
+                            || matches!(
+                                (indent, self.config.indent_style),
+                                (IndentChar::Space, Some(IndentStyle::Tab))
+                                    | (IndentChar::Tab, Some(IndentStyle::Space))
+                            );
+                        self.state = State::Indent {
+                            len: len + 1,
+                            width,
+                            style_error,
+                        };
                     }
-                    _ => {}
                 }
+                let width = match indent {
+                    IndentChar::Space => 1,
+                    IndentChar::Tab => {
+                        let tab_width = self.config.tab_width_or_default();
+                        tab_width - self.width % tab_width
+                    }
+                };
+                self.add_width(width);
                 self.col += 1;
                 self.prev_newline = None;
+                self.last_newline = None;
             }
             Character::NewLine(newline) => {
-                let trailing = match self.state {
-                    State::NonIndentWhitespace { len } => Some(len),
-                    State::Indent { len, style_error } => {
-                        if style_error {
-                            self.push_diagnosis(Diagnosis {
-                                line: self.line,
-                                range: (self.col - len, self.col),
-                                reason: Reason::IndentStyle,
-                            });
-                        }
-                        Some(len)
-                    }
-                    _ => None,
-                };
-                if let Some(len) = trailing {
-                    if len != 0 && self.config.trim_trailing_whitespace.unwrap_or(false) {
-                        self.push_diagnosis(Diagnosis {
-                            line: self.line,
-                            range: (self.col - len, self.col),
-                            reason: Reason::TrailingWhiteSpaces,
-                        });
-                    }
-                }
+                self.check_trailing_whitespaces();
+                self.check_line_length();
+                let is_crlf =
+                    newline == NewLineChar::Lf && self.prev_newline == Some(NewLineChar::Cr);
                 self.state = State::Indent {
                     len: 0,
+                    width: 0,
                     style_error: false,
                 };
 
@@ -164,91 +456,74 @@ impl<'a> CheckState<'a> {
                     (_, None) => {
                         self.prev_newline = Some(newline);
                     }
-                    (NewLineChar::Lf, Some(NewLineChar::Lf)) => {
-                        if self
-                            .config
-                            .end_of_line
-                            .map(|v| v != LineEnding::Lf)
-                            .unwrap_or(false)
-                        {
-                            self.push_diagnosis(Diagnosis {
-                                line: self.line,
-                                range: (self.col - 1, self.col),
-                                reason: Reason::EndOfLineMismatch,
-                            })
-                        }
-                        self.prev_newline = Some(NewLineChar::Lf);
-                        self.move_next_line();
-                    }
                     (NewLineChar::Lf, Some(NewLineChar::Cr)) => {
-                        if self
-                            .config
-                            .end_of_line
-                            .map(|v| v != LineEnding::Crlf)
-                            .unwrap_or(false)
-                        {
-                            self.push_diagnosis(Diagnosis {
-                                line: self.line,
-                                range: (self.col - 1, self.col),
-                                reason: Reason::EndOfLineMismatch,
-                            })
-                        }
+                        self.check_newline(LineEnding::Crlf);
                         self.prev_newline = None;
                         self.move_next_line();
                     }
-                    (NewLineChar::Cr, Some(NewLineChar::Lf)) => {
-                        self.push_diagnosis(Diagnosis {
-                            line: self.line,
-                            range: (self.col - 1, self.col),
-                            reason: Reason::EndOfLineMismatch,
-                        });
-                        self.prev_newline = Some(NewLineChar::Cr);
+                    (_, Some(prev)) => {
+                        self.check_newline(prev.into());
+                        self.prev_newline = Some(newline);
                         self.move_next_line();
                     }
-                    (NewLineChar::Cr, Some(NewLineChar::Cr)) => {
-                        if self
-                            .config
-                            .end_of_line
-                            .map(|v| v != LineEnding::Cr)
-                            .unwrap_or(false)
-                        {
-                            self.push_diagnosis(Diagnosis {
-                                line: self.line,
-                                range: (self.col - 1, self.col),
-                                reason: Reason::EndOfLineMismatch,
-                            });
-                        }
-                        self.prev_newline = Some(NewLineChar::Cr);
-                        self.move_next_line();
+                }
+                // after the previous newline is counted
+                if is_crlf {
+                    if let Some((_, range)) = &mut self.last_newline {
+                        range.1 += 1;
                     }
+                } else {
+                    self.last_newline = Some((self.line, (self.col, self.col + 1)));
                 }
             }
-            Character::Valid(_) => {
+            Character::Valid(bytes) => {
                 match self.state {
-                    State::Indent { len, style_error } => {
+                    State::Indent {
+                        len,
+                        width,
+                        style_error,
+                    } => {
                         self.check_end_of_newline();
-                        self.check_end_of_indent(len, style_error);
+                        self.check_end_of_indent(len, width, style_error);
                     }
                     State::NonWhitespace | State::NonIndentWhitespace { .. } => {}
                 }
                 self.state = State::NonWhitespace;
+                let width = self.char_width(&bytes);
+                self.add_width(width);
                 self.col += 1;
+                self.last_newline = None;
             }
-            Character::Invalid(_) | Character::Bom => {
+            Character::Invalid(_)
+            | Character::Bom
+            | Character::ReversedBom
+            | Character::Control(_) => {
                 match self.state {
-                    State::Indent { len, style_error } => {
+                    State::Indent {
+                        len,
+                        width,
+                        style_error,
+                    } => {
                         self.check_end_of_newline();
-                        self.check_end_of_indent(len, style_error);
+                        self.check_end_of_indent(len, width, style_error);
                     }
                     State::NonWhitespace | State::NonIndentWhitespace { .. } => {}
                 }
+                let reason = match ch {
+                    Character::Control(bytes) => Reason::ControlCharacter {
+                        code_point: bytes.buffer[0] as u32,
+                    },
+                    _ => Reason::InvalidCharacter,
+                };
                 self.push_diagnosis(Diagnosis {
                     line: self.line,
-                    range: (self.col - 1, self.col),
-                    reason: Reason::InvalidCharacter,
+                    range: (self.col, self.col + 1),
+                    reason,
                 });
                 self.state = State::NonWhitespace;
+                self.add_width(1);
                 self.col += 1;
+                self.last_newline = None;
             }
         }
     }
@@ -260,9 +535,14 @@ pub fn check<R: std::io::BufRead>(input: R, config: Config) -> std::io::Result<V
         col: 1,
         state: State::Indent {
             len: 0,
+            width: 0,
             style_error: false,
         },
         prev_newline: None,
+        last_newline: None,
+        width: 0,
+        too_long_from: None,
+        utf8_sequence: Vec::new(),
         diagnosis: Vec::new(),
         config: &config,
     };
@@ -278,7 +558,7 @@ pub fn check<R: std::io::BufRead>(input: R, config: Config) -> std::io::Result<V
             if ch != Some(Character::Bom) {
                 state.push_diagnosis(Diagnosis {
                     line: 1,
-                    range: (0, 0),
+                    range: (1, 1),
                     reason: Reason::BomNotFound,
                 });
 
@@ -287,19 +567,27 @@ pub fn check<R: std::io::BufRead>(input: R, config: Config) -> std::io::Result<V
                 }
             }
         }
-        Some(Charset::Utf16BigEndian) | Some(Charset::Utf16LittleEndian) => {
-            let ch = reader.next()?;
-            if ch != Some(Character::Bom) {
-                if let Some(ch) = ch {
-                    state.check_ch(ch);
-                }
-            }
-        }
+        Some(Charset::Utf16BigEndian) | Some(Charset::Utf16LittleEndian) => match reader.next()? {
+            Some(Character::Bom) | None => {}
+            Some(Character::ReversedBom) => state.push_diagnosis(Diagnosis {
+                line: 1,
+                range: (1, 1),
+                reason: Reason::BomMismatch,
+            }),
+            Some(ch) => state.check_ch(ch),
+        },
     }
 
     while let Some(ch) = reader.next()? {
-        state.check_ch(ch)
+        let is_valid = matches!(ch, Character::Valid(_));
+        state.check_ch(ch);
+        if is_valid {
+            // following printable characters don't change the state
+            let len = reader.read_ascii_run(&mut |_| Ok(()))?;
+            state.add_ascii_run(len);
+        }
     }
+    state.check_end_of_input();
 
     Ok(state.diagnosis)
 }
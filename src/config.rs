@@ -1,50 +1,60 @@
 use std::{
+    collections::BTreeMap,
     fmt::Display,
-    path::{Path, PathBuf},
-    str::FromStr,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
 };
 
 use linked_hash_map::LinkedHashMap;
-use serde::Deserializer;
-use serde_with::{serde_as, DisplayFromStr};
-
-pub fn deserialize_optional_from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
-where
-    D: Deserializer<'de>,
-    T: FromStr,
-    T::Err: Display,
-{
-    let result: Option<String> =
-        serde_with::rust::unwrap_or_skip::deserialize(deserializer).unwrap_or(None);
-
-    Ok(if let Some(s) = result {
-        Some(
-            T::from_str(&s)
-                .map_err(|e| serde::de::Error::custom(format!("Failed to parse - {e}")))?,
-        )
-    } else {
-        None
-    })
-}
+use serde::de::IntoDeserializer;
+
+use crate::Pattern;
 
-#[serde_as]
-#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+/// Properties of a section in `.editorconfig` notation.
+/// Names and values of known properties are lowercased.
+pub type Properties = LinkedHashMap<String, String>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RawConfig {
-    #[serde(default)]
-    #[serde_as(as = "DisplayFromStr")]
     pub root: bool,
-    #[serde(flatten)]
-    pub configs: RawConfigs,
+    /// section names and their properties. last item has high priority
+    pub sections: Vec<(String, Properties)>,
 }
 
-#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum IndentStyle {
     Space,
     Tab,
 }
 
-#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+impl Display for IndentStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            IndentStyle::Space => "space",
+            IndentStyle::Tab => "tab",
+        })
+    }
+}
+
+/// Value of `indent_size`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentSize {
+    /// Same as `tab_width`
+    Tab,
+    Columns(usize),
+}
+
+impl Display for IndentSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IndentSize::Tab => f.write_str("tab"),
+            IndentSize::Columns(columns) => columns.fmt(f),
+        }
+    }
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum LineEnding {
     Lf,
@@ -52,11 +62,22 @@ pub enum LineEnding {
     Cr,
 }
 
-#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+impl Display for LineEnding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            LineEnding::Lf => "lf",
+            LineEnding::Crlf => "crlf",
+            LineEnding::Cr => "cr",
+        })
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum Charset {
     #[serde(rename = "latin1")]
     Latin1,
     #[serde(rename = "utf-8")]
+    #[default]
     Utf8,
     #[serde(rename = "utf-8-bom")]
     Utf8WithBom,
@@ -66,144 +87,378 @@ pub enum Charset {
     Utf16LittleEndian,
 }
 
-impl Default for Charset {
-    fn default() -> Self {
-        Charset::Utf8
+impl Display for Charset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Charset::Latin1 => "latin1",
+            Charset::Utf8 => "utf-8",
+            Charset::Utf8WithBom => "utf-8-bom",
+            Charset::Utf16BigEndian => "utf-16be",
+            Charset::Utf16LittleEndian => "utf-16le",
+        })
     }
 }
 
-#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Config {
     pub indent_style: Option<IndentStyle>,
-    #[serde(default, deserialize_with = "deserialize_optional_from_str")]
-    pub indent_size: Option<usize>,
-    #[serde(default, deserialize_with = "deserialize_optional_from_str")]
+    pub indent_size: Option<IndentSize>,
     pub tab_width: Option<usize>,
     pub end_of_line: Option<LineEnding>,
     pub charset: Option<Charset>,
-    #[serde(default, deserialize_with = "deserialize_optional_from_str")]
     pub trim_trailing_whitespace: Option<bool>,
-    #[serde(default, deserialize_with = "deserialize_optional_from_str")]
     pub insert_final_newline: Option<bool>,
+    /// `None` if `off`
+    pub max_line_length: Option<usize>,
+    /// properties which are not used by this crate
+    pub unknown: BTreeMap<String, String>,
 }
 
-/// last item has high priority
-#[derive(serde::Deserialize, Debug, Clone, PartialEq, Eq)]
-pub struct RawConfigs(pub LinkedHashMap<String, Config>);
-
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
-    #[error("Failed to parse {1}: {0}")]
-    ParseError(serde_ini::de::Error, PathBuf),
     #[error("Failed to canonicalize given path")]
     PathCanonicalizeError(std::io::Error),
     #[error("Failed to open config file at {1}: {0}")]
     ConfigOpenError(std::io::Error, String),
-    #[error("Failed to parse glob pattern: {0}")]
-    PathPatternError(String),
     #[error("Failed to find matched config")]
     NotFound,
 }
 
 const CONFIG_FILENAME: &str = ".editorconfig";
 
-fn parse_pattern(mut s: &str) -> Result<impl Iterator<Item = Result<glob::Pattern, Error>>, Error> {
-    fn expand(mut prefixes: Vec<String>, s: &str) -> Result<(Vec<String>, &str), Error> {
-        if let Some(begin_pos) = s.find('{') {
-            if let Some(end_pos) = s[begin_pos..].find('}') {
-                let prev = &s[0..begin_pos];
-                let inner = &s[(begin_pos + 1)..end_pos];
-                if let Some((num1, num2)) = inner.split_once("..") {
-                    let num1: i32 = num1.parse().map_err(|e| {
-                        Error::PathPatternError(format!(
-                            "Failed to expand number range pattern. Found invalid number - {e}"
-                        ))
-                    })?;
-                    let num2: i32 = num2.parse().map_err(|e| {
-                        Error::PathPatternError(format!(
-                            "Failed to expand number range pattern. Found invalid number - {e}"
-                        ))
-                    })?;
-
-                    Ok((
-                        prefixes
-                            .iter()
-                            .flat_map(|prefix| {
-                                (num1..=num2)
-                                    .into_iter()
-                                    .map(move |i| format!("{prefix}{prev}{i}"))
-                            })
-                            .collect(),
-                        &s[end_pos..],
-                    ))
+/// Properties which are parsed into fields of [`Config`]
+pub const KNOWN_PROPERTIES: [&str; 8] = [
+    "indent_style",
+    "indent_size",
+    "tab_width",
+    "end_of_line",
+    "charset",
+    "trim_trailing_whitespace",
+    "insert_final_newline",
+    "max_line_length",
+];
+
+/// Width of a tab character if neither `tab_width` nor `indent_size` is set
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+/// Value which removes the inherited value of a property
+const UNSET: &str = "unset";
+
+fn parse_enum<T: serde::de::DeserializeOwned>(value: &str) -> Option<T> {
+    let deserializer: serde::de::value::StrDeserializer<serde::de::value::Error> =
+        value.into_deserializer();
+    T::deserialize(deserializer).ok()
+}
+
+impl Config {
+    /// Value of the property named `name` in `.editorconfig` notation
+    pub fn property(&self, name: &str) -> Option<String> {
+        match name {
+            "indent_style" => self.indent_style.map(|v| v.to_string()),
+            "indent_size" => self.indent_size.map(|v| v.to_string()),
+            "tab_width" => self.tab_width.map(|v| v.to_string()),
+            "end_of_line" => self.end_of_line.map(|v| v.to_string()),
+            "charset" => self.charset.map(|v| v.to_string()),
+            "trim_trailing_whitespace" => self.trim_trailing_whitespace.map(|v| v.to_string()),
+            "insert_final_newline" => self.insert_final_newline.map(|v| v.to_string()),
+            "max_line_length" => self.max_line_length.map(|v| v.to_string()),
+            _ => self.unknown.get(name).cloned(),
+        }
+    }
+
+    /// Every property which is set, in `.editorconfig` notation
+    pub fn properties(&self) -> BTreeMap<String, String> {
+        let mut properties = self.unknown.clone();
+        for name in KNOWN_PROPERTIES {
+            if let Some(value) = self.property(name) {
+                properties.insert(name.to_string(), value);
+            }
+        }
+        properties
+    }
+
+    /// Set the property named `name`. `unset` clears the property.
+    /// Returns `false` if `value` is invalid for a known property.
+    pub fn set(&mut self, name: &str, value: &str) -> bool {
+        fn update<T>(
+            field: &mut Option<T>,
+            value: &str,
+            parse: impl Fn(&str) -> Option<T>,
+        ) -> bool {
+            if value == UNSET {
+                *field = None;
+                return true;
+            }
+            match parse(value) {
+                Some(value) => {
+                    *field = Some(value);
+                    true
+                }
+                None => false,
+            }
+        }
+        match name {
+            "indent_style" => update(&mut self.indent_style, value, parse_enum),
+            "indent_size" => update(&mut self.indent_size, value, |v| match v {
+                "tab" => Some(IndentSize::Tab),
+                _ => v.parse().ok().map(IndentSize::Columns),
+            }),
+            "tab_width" => update(&mut self.tab_width, value, |v| v.parse().ok()),
+            "end_of_line" => update(&mut self.end_of_line, value, parse_enum),
+            "charset" => update(&mut self.charset, value, parse_enum),
+            "trim_trailing_whitespace" => update(&mut self.trim_trailing_whitespace, value, |v| {
+                v.parse().ok()
+            }),
+            "insert_final_newline" => {
+                update(&mut self.insert_final_newline, value, |v| v.parse().ok())
+            }
+            "max_line_length" if value == "off" => {
+                self.max_line_length = None;
+                true
+            }
+            "max_line_length" => update(&mut self.max_line_length, value, |v| v.parse().ok()),
+            _ => {
+                if value.eq_ignore_ascii_case(UNSET) {
+                    self.unknown.remove(name);
                 } else {
-                    let items = inner.split(',');
-
-                    Ok((
-                        prefixes
-                            .iter()
-                            .flat_map(|prefix| {
-                                items.clone().map(move |i| format!("{prefix}{prev}{i}"))
-                            })
-                            .collect(),
-                        &s[(end_pos + 1)..],
-                    ))
+                    self.unknown.insert(name.to_string(), value.to_string());
                 }
-            } else {
-                Err(Error::PathPatternError(
-                    "Matched } is not found".to_string(),
-                ))
+                true
+            }
+        }
+    }
+
+    /// Build config from merged properties
+    fn from_properties(properties: &Properties) -> Config {
+        let mut config = Config::default();
+        for (name, value) in properties {
+            // invalid values are ignored like editors do, so that the property is not set
+            config.set(name, value);
+        }
+
+        // defaults derived from other properties
+        if config.indent_style == Some(IndentStyle::Tab) && config.indent_size.is_none() {
+            config.indent_size = Some(IndentSize::Tab);
+        }
+        match (config.indent_size, config.tab_width) {
+            (Some(IndentSize::Tab), Some(tab_width)) => {
+                config.indent_size = Some(IndentSize::Columns(tab_width))
+            }
+            (Some(IndentSize::Columns(columns)), None) => config.tab_width = Some(columns),
+            _ => {}
+        }
+        config
+    }
+
+    /// Columns of a tab character
+    pub fn tab_width_or_default(&self) -> usize {
+        let indent_size = match self.indent_size {
+            Some(IndentSize::Columns(columns)) => Some(columns),
+            _ => None,
+        };
+        self.tab_width
+            .or(indent_size)
+            .unwrap_or(DEFAULT_TAB_WIDTH)
+            .max(1)
+    }
+
+    /// Columns of an indentation level. `None` if `indent_size` is not set.
+    pub fn indent_width(&self) -> Option<usize> {
+        match self.indent_size? {
+            IndentSize::Tab => Some(self.tab_width_or_default()),
+            IndentSize::Columns(columns) => Some(columns),
+        }
+    }
+
+    /// Resolve the effective config for `path`.
+    /// Every matched section of every `.editorconfig` up to `root = true` is merged.
+    /// Later sections and nearer files have higher priority.
+    pub fn get_config_for(path: &Path) -> Result<Config, Error> {
+        Config::get_config_for_with(path, PathResolution::default())
+    }
+
+    /// Same as [`Config::get_config_for`] with the given way to resolve `path`.
+    pub fn get_config_for_with(path: &Path, resolution: PathResolution) -> Result<Config, Error> {
+        resolve_config(path, resolution, |config_path| {
+            Ok(RawConfig::open(config_path)?.map(|config| Arc::new(config.compile())))
+        })
+    }
+}
+
+impl RawConfig {
+    /// Parse the content of `.editorconfig`.
+    /// Lines which are neither a section nor a property are ignored.
+    /// Values are kept even if they are invalid.
+    pub fn parse(content: &str) -> RawConfig {
+        let mut config = RawConfig {
+            root: false,
+            sections: Vec::new(),
+        };
+        let content = content.strip_prefix('\u{FEFF}').unwrap_or(content);
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with([';', '#']) {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                config.sections.push((name.to_string(), Properties::new()));
+                continue;
+            }
+            let Some((name, value)) = line.split_once('=') else {
+                continue;
+            };
+            let name = name.trim().to_lowercase();
+            let mut value = value.trim().to_string();
+            if KNOWN_PROPERTIES.contains(&name.as_str()) || name == "root" {
+                value = value.to_lowercase();
             }
-        } else {
-            for pref in &mut prefixes {
-                pref.push_str(s);
+            match config.sections.last_mut() {
+                Some((_, properties)) => {
+                    properties.insert(name, value);
+                }
+                None if name == "root" => config.root = value == "true",
+                None => {}
             }
+        }
+        config
+    }
 
-            Ok((prefixes, ""))
+    /// Parse the config file at `path`. Returns `None` if it is not a file.
+    pub fn open(path: &Path) -> Result<Option<RawConfig>, Error> {
+        if !path.is_file() {
+            return Ok(None);
         }
+        let content = std::fs::read(path)
+            .map_err(|e| Error::ConfigOpenError(e, path.to_string_lossy().to_string()))?;
+        Ok(Some(RawConfig::parse(&String::from_utf8_lossy(&content))))
     }
-    let mut expanded_patterns = vec!["".to_string()];
-    while !s.is_empty() {
-        (expanded_patterns, s) = expand(expanded_patterns, s)?;
+
+    /// Compile section patterns so that the config can be matched against many paths.
+    pub(crate) fn compile(&self) -> CompiledConfig {
+        CompiledConfig {
+            root: self.root,
+            sections: self
+                .sections
+                .iter()
+                .map(|(section, properties)| (Pattern::new(section), properties.clone()))
+                .collect(),
+        }
     }
+}
 
-    Ok(expanded_patterns.into_iter().map(|pattern| {
-        glob::Pattern::new(&pattern).map_err(|e| Error::PathPatternError(e.to_string()))
-    }))
+/// [`RawConfig`] with compiled section patterns
+pub(crate) struct CompiledConfig {
+    root: bool,
+    /// last item has high priority
+    sections: Vec<(Pattern, Properties)>,
 }
 
-impl Config {
-    pub fn get_config_for(path: &Path) -> Result<Config, Error> {
-        let canonicalized_path = path.canonicalize().map_err(Error::PathCanonicalizeError)?;
-        for dir in canonicalized_path.ancestors() {
-            let mut config_path = dir.with_file_name(CONFIG_FILENAME);
-            if config_path.is_file() {
-                let file = std::fs::File::open(&config_path).map_err(|e| {
-                    Error::ConfigOpenError(e, config_path.to_string_lossy().to_string())
-                })?;
-                let config: RawConfig = match serde_ini::from_read(file) {
-                    Ok(c) => c,
-                    Err(e) => return Err(Error::ParseError(e, config_path)),
-                };
-                let is_root = config.root;
-
-                config_path.pop();
-                let relative_path = canonicalized_path.strip_prefix(&config_path).unwrap();
-                for (pattern, config) in config.configs.0.into_iter().rev() {
-                    for pattern in parse_pattern(&pattern)? {
-                        let pattern = pattern?;
-                        if pattern.matches_path(relative_path) {
-                            return Ok(config);
-                        }
+impl CompiledConfig {
+    /// Merge sections matching `relative_path` into `merged`
+    fn apply(&self, relative_path: &Path, merged: &mut Option<Properties>) {
+        for (pattern, properties) in self.sections.iter().rev() {
+            if pattern.matches_path(relative_path) {
+                let merged = merged.get_or_insert_with(Properties::new);
+                for (name, value) in properties {
+                    if !merged.contains_key(name) {
+                        merged.insert(name.clone(), value.clone());
                     }
                 }
+            }
+        }
+    }
+}
 
-                if is_root {
-                    break;
+/// How a path is made absolute before it is matched against sections
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum PathResolution {
+    /// Resolve symlinks and `..` with the file system.
+    /// A symlinked file gets the config of its target's directory
+    #[default]
+    Canonical,
+    /// Join with the current directory and normalize `.` and `..` lexically.
+    /// If `follow_symlinks` is false, the file system is not touched and
+    /// a symlinked file gets the config of the directory of the link
+    Lexical { follow_symlinks: bool },
+}
+
+impl PathResolution {
+    fn resolve(self, path: &Path) -> std::io::Result<PathBuf> {
+        match self {
+            PathResolution::Canonical => canonicalize(path),
+            PathResolution::Lexical { follow_symlinks } => {
+                let path = normalize(path)?;
+                if follow_symlinks {
+                    canonicalize(&path)
+                } else {
+                    Ok(path)
                 }
             }
         }
+    }
+}
 
-        Err(Error::NotFound)
+/// Canonicalize `path` which may not exist.
+/// Missing components are appended to the nearest existing ancestor.
+fn canonicalize(path: &Path) -> std::io::Result<PathBuf> {
+    match path.canonicalize() {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+                return Err(e);
+            };
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            Ok(canonicalize(parent)?.join(name))
+        }
+        result => result,
     }
 }
+
+/// Absolute path of `path` with `.` and `..` removed without accessing the file system
+pub(crate) fn normalize(path: &Path) -> std::io::Result<PathBuf> {
+    let mut normalized = if path.is_absolute() {
+        PathBuf::new()
+    } else {
+        std::env::current_dir()?
+    };
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    Ok(normalized)
+}
+
+/// Resolve the effective config for `path` with config files given by `load`.
+/// `path` does not need to exist.
+pub(crate) fn resolve_config(
+    path: &Path,
+    resolution: PathResolution,
+    mut load: impl FnMut(&Path) -> Result<Option<Arc<CompiledConfig>>, Error>,
+) -> Result<Config, Error> {
+    let canonicalized_path = resolution
+        .resolve(path)
+        .map_err(Error::PathCanonicalizeError)?;
+    let mut merged: Option<Properties> = None;
+    for dir in canonicalized_path.ancestors().skip(1) {
+        if let Some(config) = load(&dir.join(CONFIG_FILENAME))? {
+            let relative_path = canonicalized_path.strip_prefix(dir).unwrap();
+            config.apply(relative_path, &mut merged);
+
+            if config.root {
+                break;
+            }
+        }
+    }
+
+    merged
+        .map(|properties| Config::from_properties(&properties))
+        .ok_or(Error::NotFound)
+}
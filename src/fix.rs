@@ -0,0 +1,236 @@
+use crate::{
+    reader::{Character, CharacterReader, IndentChar, NewLineChar, Reader},
+    Charset, Config, IndentStyle, LineEnding,
+};
+
+struct FixState<'a, W: std::io::Write> {
+    out: W,
+    config: &'a Config,
+    /// leading whitespaces of current line. `None` after first non-whitespace character
+    indent: Option<Vec<IndentChar>>,
+    /// whitespaces after first non-whitespace character which is not written yet
+    pending_whitespaces: Vec<IndentChar>,
+    /// newlines which is not written yet. These are dropped at the end of input if required
+    pending_newlines: Vec<LineEnding>,
+    prev_cr: bool,
+    has_content: bool,
+}
+
+impl<'a, W: std::io::Write> FixState<'a, W> {
+    fn write_ascii(&mut self, ch: u8) -> std::io::Result<()> {
+        match self.config.charset {
+            Some(Charset::Utf16LittleEndian) => self.out.write_all(&[ch, 0]),
+            Some(Charset::Utf16BigEndian) => self.out.write_all(&[0, ch]),
+            _ => self.out.write_all(&[ch]),
+        }
+    }
+
+    fn write_bom(&mut self) -> std::io::Result<()> {
+        match self.config.charset {
+            Some(Charset::Utf8WithBom) => self.out.write_all("\u{FEFF}".as_bytes()),
+            Some(Charset::Utf16LittleEndian) => self.out.write_all(&[0xFF, 0xFE]),
+            Some(Charset::Utf16BigEndian) => self.out.write_all(&[0xFE, 0xFF]),
+            _ => Ok(()),
+        }
+    }
+
+    fn write_whitespaces(&mut self, chars: &[IndentChar]) -> std::io::Result<()> {
+        for ch in chars {
+            self.write_ascii(match ch {
+                IndentChar::Space => b' ',
+                IndentChar::Tab => b'\t',
+            })?;
+        }
+        Ok(())
+    }
+
+    fn flush_newlines(&mut self) -> std::io::Result<()> {
+        for newline in std::mem::take(&mut self.pending_newlines) {
+            let newline = self.config.end_of_line.unwrap_or(newline);
+            match newline {
+                LineEnding::Lf => self.write_ascii(b'\n')?,
+                LineEnding::Cr => self.write_ascii(b'\r')?,
+                LineEnding::Crlf => {
+                    self.write_ascii(b'\r')?;
+                    self.write_ascii(b'\n')?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Indentation in `indent_style` whose width is rounded up to a multiple of `indent_size`.
+    /// Without `indent_style`, spaces are appended to the indentation as is.
+    fn fix_indent(&self, indent: &[IndentChar]) -> Vec<IndentChar> {
+        let tab_width = self.config.tab_width_or_default();
+        let width = indent.iter().fold(0, |width, ch| match ch {
+            IndentChar::Space => width + 1,
+            IndentChar::Tab => (width / tab_width + 1) * tab_width,
+        });
+        let round_up = |width: usize, unit: usize| width.div_ceil(unit) * unit;
+        let rounded = match self.config.indent_width() {
+            Some(size) if size > 0 => round_up(width, size),
+            _ => width,
+        };
+
+        match self.config.indent_style {
+            Some(IndentStyle::Space) => vec![IndentChar::Space; rounded],
+            Some(IndentStyle::Tab) => {
+                vec![IndentChar::Tab; round_up(rounded, tab_width) / tab_width]
+            }
+            None => {
+                let mut fixed = indent.to_vec();
+                fixed.resize(indent.len() + rounded - width, IndentChar::Space);
+                fixed
+            }
+        }
+    }
+
+    /// Write out whitespaces and newlines which precede non-whitespace character.
+    fn begin_content(&mut self) -> std::io::Result<()> {
+        self.flush_newlines()?;
+        if let Some(indent) = self.indent.take() {
+            let indent = self.fix_indent(&indent);
+            self.write_whitespaces(&indent)?;
+        }
+        let whitespaces = std::mem::take(&mut self.pending_whitespaces);
+        self.write_whitespaces(&whitespaces)?;
+        self.has_content = true;
+        Ok(())
+    }
+
+    fn end_line(&mut self, newline: LineEnding) -> std::io::Result<()> {
+        let trim = self.config.trim_trailing_whitespace.unwrap_or(false);
+        let indent = self.indent.take();
+        if trim {
+            self.pending_whitespaces.clear();
+        } else if indent.as_ref().map(|i| !i.is_empty()).unwrap_or(false)
+            || !self.pending_whitespaces.is_empty()
+        {
+            self.indent = indent;
+            self.begin_content()?;
+        }
+        self.indent = Some(Vec::new());
+        self.pending_newlines.push(newline);
+        self.has_content = true;
+        Ok(())
+    }
+
+    fn take_cr(&mut self) -> std::io::Result<()> {
+        if std::mem::take(&mut self.prev_cr) {
+            self.end_line(LineEnding::Cr)?;
+        }
+        Ok(())
+    }
+
+    fn fix_ch(&mut self, ch: Character) -> std::io::Result<()> {
+        match ch {
+            Character::NewLine(NewLineChar::Cr) => {
+                self.take_cr()?;
+                self.prev_cr = true;
+            }
+            Character::NewLine(NewLineChar::Lf) => {
+                if std::mem::take(&mut self.prev_cr) {
+                    self.end_line(LineEnding::Crlf)?;
+                } else {
+                    self.end_line(LineEnding::Lf)?;
+                }
+            }
+            Character::Indent(indent) => {
+                self.take_cr()?;
+                match &mut self.indent {
+                    Some(indents) => indents.push(indent),
+                    None => self.pending_whitespaces.push(indent),
+                }
+            }
+            Character::Bom => {
+                // BOM is only allowed at the beginning of the input
+                self.take_cr()?;
+            }
+            Character::ReversedBom => {
+                // the file may be encoded in the other byte order. keep it as is
+                self.take_cr()?;
+                self.begin_content()?;
+                match self.config.charset {
+                    Some(Charset::Utf16BigEndian) => self.out.write_all(&[0xFF, 0xFE])?,
+                    _ => self.out.write_all(&[0xFE, 0xFF])?,
+                }
+            }
+            Character::Valid(bytes) | Character::Invalid(bytes) | Character::Control(bytes) => {
+                self.take_cr()?;
+                self.begin_content()?;
+                self.out.write_all(bytes.as_slice())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> std::io::Result<W> {
+        self.take_cr()?;
+        let trim = self.config.trim_trailing_whitespace.unwrap_or(false);
+        let has_trailing_whitespaces = self.indent.as_ref().map(|i| !i.is_empty()).unwrap_or(false)
+            || !self.pending_whitespaces.is_empty();
+        if has_trailing_whitespaces && !trim {
+            self.begin_content()?;
+        }
+
+        match self.config.insert_final_newline {
+            Some(true) => {
+                if self.pending_newlines.is_empty() && self.indent.is_none() && self.has_content {
+                    self.pending_newlines.push(LineEnding::Lf);
+                }
+                self.flush_newlines()?;
+            }
+            Some(false) => self.pending_newlines.clear(),
+            None => self.flush_newlines()?,
+        }
+
+        Ok(self.out)
+    }
+}
+
+/// Rewrite `input` into `output` so that [`crate::check`] does not report fixable reasons.
+/// Invalid and control characters are written as is.
+pub fn fix<R: std::io::BufRead, W: std::io::Write>(
+    input: R,
+    output: W,
+    config: &Config,
+) -> std::io::Result<W> {
+    let mut state = FixState {
+        out: output,
+        config,
+        indent: Some(Vec::new()),
+        pending_whitespaces: Vec::new(),
+        pending_newlines: Vec::new(),
+        prev_cr: false,
+        has_content: false,
+    };
+
+    let mut reader = CharacterReader::new(input, config.charset);
+
+    let first = reader.next()?;
+    match config.charset {
+        Some(Charset::Utf8WithBom) => state.write_bom()?,
+        Some(Charset::Utf16BigEndian) | Some(Charset::Utf16LittleEndian) => {
+            if first == Some(Character::Bom) {
+                state.write_bom()?;
+            }
+        }
+        Some(Charset::Latin1) | Some(Charset::Utf8) | None => {}
+    }
+    if let Some(ch) = first {
+        if ch != Character::Bom {
+            state.fix_ch(ch)?;
+        }
+    }
+
+    while let Some(ch) = reader.next()? {
+        let is_valid = matches!(ch, Character::Valid(_));
+        state.fix_ch(ch)?;
+        if is_valid {
+            reader.read_ascii_run(&mut |bytes| state.out.write_all(bytes))?;
+        }
+    }
+
+    state.finish()
+}
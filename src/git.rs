@@ -0,0 +1,203 @@
+//! Changed lines from the local git repository
+
+use std::{
+    collections::HashMap,
+    ops::Range,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::Context;
+use editorconfig_lint::Diagnosis;
+
+/// Lines of files which differ from a revision. Paths are canonicalized.
+pub struct ChangedLines {
+    /// canonicalized top-level directory of the working tree
+    root: PathBuf,
+    /// 1-based line ranges of each changed file. A file may have no range if lines are only removed
+    files: HashMap<PathBuf, Vec<Range<usize>>>,
+}
+
+/// Stdout of git. It is not decoded since file content may be in any charset
+fn git(args: &[&str]) -> anyhow::Result<Vec<u8>> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+/// `start[,count]` of a hunk header
+fn parse_hunk_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+/// Path of a `+++` line, which is C-quoted if it contains special characters
+/// and followed by a tab if it contains spaces
+fn parse_file_name(name: &str) -> Option<String> {
+    let name = name.strip_suffix('\t').unwrap_or(name);
+    let Some(quoted) = name.strip_prefix('"') else {
+        return Some(name.to_string());
+    };
+    let quoted = quoted.strip_suffix('"')?.as_bytes();
+    let mut bytes = Vec::new();
+    let mut i = 0;
+    while i < quoted.len() {
+        if quoted[i] != b'\\' {
+            bytes.push(quoted[i]);
+            i += 1;
+            continue;
+        }
+        let escaped = *quoted.get(i + 1)?;
+        i += 2;
+        bytes.push(match escaped {
+            b'a' => 0x07,
+            b'b' => 0x08,
+            b'f' => 0x0C,
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'v' => 0x0B,
+            b'0'..=b'7' => {
+                let octal = std::str::from_utf8(quoted.get(i - 1..i + 2)?).ok()?;
+                i += 2;
+                u8::from_str_radix(octal, 8).ok()?
+            }
+            byte => byte,
+        });
+    }
+    String::from_utf8(bytes).ok()
+}
+
+impl ChangedLines {
+    /// Lines of the working tree changed since `base`, or lines of the index if `staged`.
+    /// Without `base`, the working tree is compared with the index, and the index with `HEAD`
+    /// if `staged`. Untracked files are entirely changed unless `staged`.
+    pub fn from_git(base: Option<&str>, staged: bool) -> anyhow::Result<ChangedLines> {
+        let root = String::from_utf8(git(&["rev-parse", "--show-toplevel"])?)?;
+        let root = PathBuf::from(root.trim_end()).canonicalize()?;
+
+        let mut args = vec![
+            "-c",
+            "core.quotePath=false",
+            "diff",
+            "--unified=0",
+            "--no-color",
+            "--no-ext-diff",
+            "--src-prefix=a/",
+            "--dst-prefix=b/",
+        ];
+        if staged {
+            args.push("--cached");
+        }
+        args.extend(base);
+        let diff = git(&args)?;
+
+        let mut files = HashMap::new();
+        let mut current = None;
+        // lines of the current hunk which are not read yet
+        let mut remaining = 0;
+        for line in diff.split(|&byte| byte == b'\n') {
+            if line.starts_with(b"\\") {
+                // `\ No newline at end of file`
+                continue;
+            }
+            if remaining > 0 {
+                remaining -= 1;
+                continue;
+            }
+            // removed and added lines are skipped above. only headers are decoded
+            if let Some(path) = line.strip_prefix(b"+++ ") {
+                let Some(path) = std::str::from_utf8(path).ok().and_then(parse_file_name) else {
+                    anyhow::bail!(
+                        "unexpected file name from git diff: {}",
+                        String::from_utf8_lossy(line)
+                    );
+                };
+                // `/dev/null` for removed files
+                current = path.strip_prefix("b/").map(|path| root.join(path));
+                if let Some(path) = &current {
+                    files.insert(path.clone(), Vec::new());
+                }
+            } else if let Some(header) = line.strip_prefix(b"@@ ") {
+                // function context after the ranges may be in any charset
+                let header = String::from_utf8_lossy(header);
+                let mut ranges = header.split(' ');
+                let old = ranges
+                    .next()
+                    .and_then(|r| parse_hunk_range(r.strip_prefix('-')?));
+                let new = ranges
+                    .next()
+                    .and_then(|r| parse_hunk_range(r.strip_prefix('+')?));
+                let (Some((_, old_count)), Some((start, count))) = (old, new) else {
+                    anyhow::bail!("unexpected hunk header from git diff: @@ {header}");
+                };
+                remaining = old_count + count;
+                if let (Some(path), true) = (&current, count > 0) {
+                    files.get_mut(path).unwrap().push(start..start + count);
+                }
+            }
+        }
+
+        if !staged {
+            let untracked = git(&[
+                "ls-files",
+                "--others",
+                "--exclude-standard",
+                "-z",
+                "--full-name",
+                ":/",
+            ])?;
+            let untracked = String::from_utf8(untracked)?;
+            for path in untracked.split('\0').filter(|path| !path.is_empty()) {
+                let whole_file = 1..usize::MAX;
+                files.insert(root.join(path), vec![whole_file]);
+            }
+        }
+        Ok(ChangedLines { root, files })
+    }
+
+    fn ranges(&self, path: &Path) -> Option<&[Range<usize>]> {
+        let path = path.canonicalize().ok()?;
+        self.files.get(&path).map(Vec::as_slice)
+    }
+
+    pub fn contains_file(&self, path: &Path) -> bool {
+        self.ranges(path).is_some()
+    }
+
+    /// Content of `path` in the index, which differs from the working tree if it is not staged
+    pub fn staged_content(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
+        let path = path.canonicalize()?;
+        let path = path
+            .strip_prefix(&self.root)?
+            .to_str()
+            .with_context(|| format!("{} is not valid unicode", path.display()))?;
+        // `/` is the separator of index paths on every platform
+        let path = path.replace(std::path::MAIN_SEPARATOR, "/");
+        git(&["cat-file", "blob", &format!(":{path}")])
+    }
+
+    /// Keep diagnoses on changed lines of `path`.
+    /// File level reasons are kept if the file is changed.
+    pub fn retain(&self, path: &Path, diagnoses: &mut Vec<Diagnosis>) {
+        let Some(ranges) = self.ranges(path) else {
+            diagnoses.clear();
+            return;
+        };
+        diagnoses.retain(|diagnosis| {
+            diagnosis.reason.is_file_level()
+                || ranges.iter().any(|range| range.contains(&diagnosis.line))
+        });
+    }
+}
@@ -1,7 +1,22 @@
 mod config;
 pub use config::*;
 
+mod pattern;
+pub use pattern::*;
+
+mod resolver;
+pub use resolver::*;
+
 mod reader;
 
 mod check;
 pub use check::*;
+
+mod fix;
+pub use fix::*;
+
+mod report;
+pub use report::*;
+
+mod baseline;
+pub use baseline::*;
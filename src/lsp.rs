@@ -0,0 +1,493 @@
+//! Language server over stdio
+
+use std::collections::HashMap;
+
+use editorconfig_lint::{
+    check, fix, Charset, Config, ConfigResolver, Diagnosis, Error, PathResolution, Reason,
+};
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidChangeWatchedFiles, DidCloseTextDocument, DidOpenTextDocument,
+        DidSaveTextDocument, LogMessage, Notification as _, PublishDiagnostics,
+    },
+    request::{CodeActionRequest, Formatting, RegisterCapability, Request as _},
+    CodeAction, CodeActionKind, CodeActionOptions, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, DiagnosticSeverity, DidChangeTextDocumentParams,
+    DidChangeWatchedFilesRegistrationOptions, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams, DidSaveTextDocumentParams, DocumentFormattingParams,
+    FileSystemWatcher, GlobPattern, InitializeParams, LogMessageParams, MessageType,
+    NumberOrString, OneOf, Position, PublishDiagnosticsParams, Range, Registration,
+    RegistrationParams, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextDocumentSyncOptions, TextDocumentSyncSaveOptions, TextEdit, Url, WorkspaceEdit,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+const CONFIG_FILENAME: &str = ".editorconfig";
+
+struct Document {
+    version: i32,
+    text: String,
+}
+
+struct Server {
+    connection: Connection,
+    resolver: ConfigResolver,
+    documents: HashMap<Url, Document>,
+}
+
+/// Lines of `text` with their line endings. The last line has no line ending and may be empty.
+fn split_lines(text: &str) -> Vec<&str> {
+    let bytes = text.as_bytes();
+    let mut lines = Vec::new();
+    let mut begin = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        i = match bytes[i] {
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => i + 2,
+            b'\r' | b'\n' => i + 1,
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        lines.push(&text[begin..i]);
+        begin = i;
+    }
+    lines.push(&text[begin..]);
+    lines
+}
+
+/// Position of 1-based `col` of 1-based `line`.
+/// A column in the line ending is the beginning of the next line.
+fn column_position(lines: &[&str], line: usize, col: usize) -> Position {
+    let text = lines.get(line - 1).copied().unwrap_or("");
+    let content = text.trim_end_matches(['\r', '\n']);
+    if col - 1 > content.chars().count() {
+        return Position::new(line as u32, 0);
+    }
+    let character = content
+        .chars()
+        .take(col - 1)
+        .map(char::len_utf16)
+        .sum::<usize>();
+    Position::new(line as u32 - 1, character as u32)
+}
+
+/// Position of byte `offset` in `text`, which must not split `\r\n`
+fn offset_position(text: &str, offset: usize) -> Position {
+    let mut position = Position::new(0, 0);
+    let mut chars = text[..offset].chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\r' | '\n' => position = Position::new(position.line + 1, 0),
+            ch => position.character += ch.len_utf16() as u32,
+        }
+    }
+    position
+}
+
+/// Edit which turns `old` into `new`. Unchanged head and tail are not included.
+fn text_edit(old: &str, new: &str) -> Option<TextEdit> {
+    if old == new {
+        return None;
+    }
+    let splits_crlf =
+        |offset: usize| old[..offset].ends_with('\r') && old[offset..].starts_with('\n');
+
+    let mut prefix = old
+        .bytes()
+        .zip(new.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(prefix) || splits_crlf(prefix) {
+        prefix -= 1;
+    }
+    let mut suffix = old
+        .bytes()
+        .rev()
+        .zip(new.bytes().rev())
+        .take(old.len().min(new.len()) - prefix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(old.len() - suffix) || splits_crlf(old.len() - suffix) {
+        suffix -= 1;
+    }
+
+    Some(TextEdit::new(
+        Range::new(
+            offset_position(old, prefix),
+            offset_position(old, old.len() - suffix),
+        ),
+        new[prefix..new.len() - suffix].to_string(),
+    ))
+}
+
+fn fix_text(text: &str, config: &Config) -> anyhow::Result<String> {
+    let fixed = fix(text.as_bytes(), Vec::new(), config)?;
+    Ok(String::from_utf8(fixed)?)
+}
+
+/// Config which only has the properties to fix `reason`, and title of the fix.
+/// `None` if the fixer does not handle `reason`.
+fn quick_fix(reason: &Reason, config: &Config) -> Option<(Config, String)> {
+    let mut fix_config = Config {
+        charset: config.charset,
+        ..Config::default()
+    };
+    let title = match reason {
+        Reason::IndentStyle { .. } | Reason::IndentSizeMismatch { .. } => {
+            fix_config.indent_style = config.indent_style;
+            fix_config.indent_size = config.indent_size;
+            fix_config.tab_width = config.tab_width;
+            match reason {
+                Reason::IndentSizeMismatch { expected, .. } => {
+                    format!("Round indentation to a multiple of {expected}")
+                }
+                _ => format!("Convert indentation to {}s", config.indent_style?),
+            }
+        }
+        Reason::EndOfLineMismatch { expected, .. } => {
+            fix_config.end_of_line = Some(*expected);
+            format!("Convert line ending to {expected}")
+        }
+        Reason::TrailingWhiteSpaces => {
+            fix_config.trim_trailing_whitespace = Some(true);
+            "Trim trailing whitespace".to_string()
+        }
+        Reason::NoFinalNewline => {
+            fix_config.insert_final_newline = Some(true);
+            "Insert final newline".to_string()
+        }
+        Reason::SuperfluousFinalNewline => {
+            fix_config.insert_final_newline = Some(false);
+            "Remove final newline".to_string()
+        }
+        Reason::BomNotFound
+        | Reason::BomMismatch
+        | Reason::InvalidCharacter
+        | Reason::ControlCharacter { .. }
+        | Reason::LineTooLong { .. } => return None,
+    };
+    Some((fix_config, title))
+}
+
+fn lsp_diagnostic(lines: &[&str], diagnosis: &Diagnosis) -> lsp_types::Diagnostic {
+    let (begin, end) = diagnosis.range;
+    lsp_types::Diagnostic {
+        range: Range::new(
+            column_position(lines, diagnosis.line, begin),
+            column_position(lines, diagnosis.line, end),
+        ),
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(NumberOrString::String(diagnosis.reason.code().to_string())),
+        source: Some("editorconfig-lint".to_string()),
+        message: diagnosis.reason.to_string(),
+        ..Default::default()
+    }
+}
+
+fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                ..Default::default()
+            },
+        )),
+        code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
+            code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+            ..Default::default()
+        })),
+        document_formatting_provider: Some(OneOf::Left(true)),
+        ..Default::default()
+    }
+}
+
+/// Response to `request` with the result of `handler`
+fn respond<P: DeserializeOwned, R: Serialize>(
+    request: Request,
+    handler: impl FnOnce(P) -> anyhow::Result<R>,
+) -> Response {
+    let params = match serde_json::from_value(request.params) {
+        Ok(params) => params,
+        Err(e) => {
+            return Response::new_err(request.id, ErrorCode::InvalidParams as i32, e.to_string())
+        }
+    };
+    match handler(params).and_then(|result| Ok(serde_json::to_value(result)?)) {
+        Ok(result) => Response::new_ok(request.id, result),
+        Err(e) => Response::new_err(request.id, ErrorCode::InternalError as i32, e.to_string()),
+    }
+}
+
+impl Server {
+    fn send_notification<N: lsp_types::notification::Notification>(
+        &self,
+        params: N::Params,
+    ) -> anyhow::Result<()> {
+        let notification = Notification::new(N::METHOD.to_string(), params);
+        self.connection.sender.send(notification.into())?;
+        Ok(())
+    }
+
+    fn log(&self, message: String) -> anyhow::Result<()> {
+        self.send_notification::<LogMessage>(LogMessageParams {
+            typ: MessageType::ERROR,
+            message,
+        })
+    }
+
+    /// Ask the client to notify changes of config files
+    fn watch_config_files(&self) -> anyhow::Result<()> {
+        let options = DidChangeWatchedFilesRegistrationOptions {
+            watchers: vec![FileSystemWatcher {
+                glob_pattern: GlobPattern::String(format!("**/{CONFIG_FILENAME}")),
+                kind: None,
+            }],
+        };
+        let params = RegistrationParams {
+            registrations: vec![Registration {
+                id: "watch-editorconfig".to_string(),
+                method: DidChangeWatchedFiles::METHOD.to_string(),
+                register_options: Some(serde_json::to_value(options)?),
+            }],
+        };
+        let request = Request::new(
+            RequestId::from("watch-editorconfig".to_string()),
+            RegisterCapability::METHOD.to_string(),
+            params,
+        );
+        self.connection.sender.send(request.into())?;
+        Ok(())
+    }
+
+    /// Config for the text of an open document. `None` if no section matches.
+    /// The editor has already decoded the file, so its charset is not checked.
+    fn config_for(&self, uri: &Url) -> anyhow::Result<Option<Config>> {
+        let Ok(path) = uri.to_file_path() else {
+            return Ok(None);
+        };
+        match self.resolver.get_config_for(&path) {
+            Ok(config) => Ok(Some(Config {
+                charset: Some(Charset::Utf8),
+                ..config
+            })),
+            Err(Error::NotFound) => Ok(None),
+            Err(e) => {
+                self.log(format!("{}: {e}", path.display()))?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn publish(&self, uri: &Url) -> anyhow::Result<()> {
+        let Some(document) = self.documents.get(uri) else {
+            return Ok(());
+        };
+        let diagnostics = match self.config_for(uri)? {
+            Some(config) => {
+                let lines = split_lines(&document.text);
+                check(document.text.as_bytes(), config)?
+                    .iter()
+                    .map(|diagnosis| lsp_diagnostic(&lines, diagnosis))
+                    .collect()
+            }
+            None => Vec::new(),
+        };
+        self.send_notification::<PublishDiagnostics>(PublishDiagnosticsParams::new(
+            uri.clone(),
+            diagnostics,
+            Some(document.version),
+        ))
+    }
+
+    /// Resolve configs of all open documents again
+    fn publish_all(&self) -> anyhow::Result<()> {
+        self.resolver.clear();
+        for uri in self.documents.keys() {
+            self.publish(uri)?;
+        }
+        Ok(())
+    }
+
+    fn code_actions(&self, params: CodeActionParams) -> anyhow::Result<Vec<CodeActionOrCommand>> {
+        let uri = params.text_document.uri;
+        let (Some(document), Some(config)) = (self.documents.get(&uri), self.config_for(&uri)?)
+        else {
+            return Ok(Vec::new());
+        };
+        let text = &document.text;
+        let lines = split_lines(text);
+
+        let mut actions = Vec::new();
+        for diagnosis in check(text.as_bytes(), config.clone())? {
+            let line = diagnosis.line as u32 - 1;
+            if line < params.range.start.line || params.range.end.line < line {
+                continue;
+            }
+            let Some((fix_config, title)) = quick_fix(&diagnosis.reason, &config) else {
+                continue;
+            };
+
+            let fixed = fix_text(text, &fix_config)?;
+            let new_text = match diagnosis.reason {
+                Reason::NoFinalNewline | Reason::SuperfluousFinalNewline => fixed,
+                // only the line of the diagnosis
+                _ => {
+                    let mut new_lines = lines.clone();
+                    new_lines[diagnosis.line - 1] = split_lines(&fixed)[diagnosis.line - 1];
+                    new_lines.concat()
+                }
+            };
+            let Some(edit) = text_edit(text, &new_text) else {
+                continue;
+            };
+
+            actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                title,
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: Some(vec![lsp_diagnostic(&lines, &diagnosis)]),
+                edit: Some(WorkspaceEdit::new(HashMap::from([(
+                    uri.clone(),
+                    vec![edit],
+                )]))),
+                is_preferred: Some(true),
+                ..Default::default()
+            }));
+        }
+        Ok(actions)
+    }
+
+    fn format(&self, params: DocumentFormattingParams) -> anyhow::Result<Vec<TextEdit>> {
+        let uri = params.text_document.uri;
+        let (Some(document), Some(config)) = (self.documents.get(&uri), self.config_for(&uri)?)
+        else {
+            return Ok(Vec::new());
+        };
+        let fixed = fix_text(&document.text, &config)?;
+        Ok(text_edit(&document.text, &fixed).into_iter().collect())
+    }
+
+    fn handle_request(&self, request: Request) -> Response {
+        match request.method.as_str() {
+            CodeActionRequest::METHOD => respond(request, |params| self.code_actions(params)),
+            Formatting::METHOD => respond(request, |params| self.format(params)),
+            method => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported request: {method}"),
+            ),
+        }
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> anyhow::Result<()> {
+        fn params<P: DeserializeOwned>(notification: Notification) -> anyhow::Result<P> {
+            Ok(serde_json::from_value(notification.params)?)
+        }
+
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams = params(notification)?;
+                let document = params.text_document;
+                self.documents.insert(
+                    document.uri.clone(),
+                    Document {
+                        version: document.version,
+                        text: document.text,
+                    },
+                );
+                self.publish(&document.uri)?;
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams = params(notification)?;
+                let uri = params.text_document.uri;
+                // changes are always whole documents with TextDocumentSyncKind::FULL
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.documents.insert(
+                        uri.clone(),
+                        Document {
+                            version: params.text_document.version,
+                            text: change.text,
+                        },
+                    );
+                }
+                self.publish(&uri)?;
+            }
+            DidSaveTextDocument::METHOD => {
+                let params: DidSaveTextDocumentParams = params(notification)?;
+                let uri = params.text_document.uri;
+                if uri.path().ends_with(&format!("/{CONFIG_FILENAME}")) {
+                    self.publish_all()?;
+                } else {
+                    self.publish(&uri)?;
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams = params(notification)?;
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                self.send_notification::<PublishDiagnostics>(PublishDiagnosticsParams::new(
+                    uri,
+                    Vec::new(),
+                    None,
+                ))?;
+            }
+            DidChangeWatchedFiles::METHOD => self.publish_all()?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn run(mut self) -> anyhow::Result<()> {
+        let receiver = self.connection.receiver.clone();
+        for message in &receiver {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        break;
+                    }
+                    let response = self.handle_request(request);
+                    self.connection.sender.send(response.into())?;
+                }
+                Message::Notification(notification) => {
+                    let method = notification.method.clone();
+                    if let Err(e) = self.handle_notification(notification) {
+                        self.log(format!("{method}: {e}"))?;
+                    }
+                }
+                // response to registration
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Serve until the client requests shutdown
+pub fn run(resolution: PathResolution) -> anyhow::Result<()> {
+    let (connection, io_threads) = Connection::stdio();
+    let params = connection.initialize(serde_json::to_value(capabilities())?)?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+
+    let server = Server {
+        connection,
+        resolver: ConfigResolver::with_path_resolution(resolution),
+        documents: HashMap::new(),
+    };
+    let can_watch = params
+        .capabilities
+        .workspace
+        .and_then(|workspace| workspace.did_change_watched_files)
+        .and_then(|capability| capability.dynamic_registration)
+        .unwrap_or(false);
+    if can_watch {
+        server.watch_config_files()?;
+    }
+    server.run()?;
+    io_threads.join()?;
+    Ok(())
+}
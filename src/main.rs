@@ -1,6 +1,25 @@
-use clap::{Parser, Subcommand};
-use editorconfig_lint::{check, Config};
-use std::{io::BufReader, path::PathBuf};
+use anyhow::Context;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use editorconfig_lint::{
+    check, fix, Baseline, Charset, CheckstyleReporter, Config, ConfigResolver, Diagnosis, Error,
+    GithubReporter, HumanReporter, JsonLinesReporter, JsonReporter, JunitReporter, PathResolution,
+    Reporter, SarifReporter, Summary, TextReporter,
+};
+use git::ChangedLines;
+use std::{
+    collections::BTreeMap,
+    io::{BufReader, IsTerminal, Read, Write},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+};
+
+mod git;
+mod lsp;
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -9,37 +28,567 @@ struct Cli {
     command: Command,
 }
 
+#[derive(Args)]
+struct WalkOptions {
+    /// Skip files matching the glob. Can be given multiple times
+    #[arg(long, value_name = "GLOB")]
+    exclude: Vec<String>,
+    /// Only check files matching the glob. Can be given multiple times
+    #[arg(long, value_name = "GLOB")]
+    include: Vec<String>,
+    /// Do not respect .gitignore, git excludes and .editorconfig-lintignore
+    #[arg(long)]
+    no_ignore: bool,
+}
+
+#[derive(Args)]
+struct ResolveOptions {
+    /// Match sections against lexically normalized absolute paths instead of canonical paths
+    #[arg(long)]
+    lexical_paths: bool,
+    /// With --lexical-paths, do not resolve symlinks so that a symlinked file gets the config of the directory of the link
+    #[arg(long, requires = "lexical_paths")]
+    no_follow_symlinks: bool,
+}
+
+impl ResolveOptions {
+    fn resolution(&self) -> PathResolution {
+        if self.lexical_paths {
+            PathResolution::Lexical {
+                follow_symlinks: !self.no_follow_symlinks,
+            }
+        } else {
+            PathResolution::Canonical
+        }
+    }
+}
+
+#[derive(Args)]
+struct StdinOptions {
+    /// Read the content from stdin instead of files
+    #[arg(long, requires = "stdin_filename", conflicts_with = "file_paths")]
+    stdin: bool,
+    /// Path which config of the content from stdin is resolved for. It does not need to exist
+    #[arg(long, value_name = "PATH", requires = "stdin")]
+    stdin_filename: Option<PathBuf>,
+}
+
+impl StdinOptions {
+    /// Path and content from stdin if `--stdin` is given
+    fn read(&self) -> std::io::Result<Option<(&Path, Vec<u8>)>> {
+        match &self.stdin_filename {
+            Some(path) if self.stdin => {
+                let mut content = Vec::new();
+                std::io::stdin().lock().read_to_end(&mut content)?;
+                Ok(Some((path, content)))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[derive(Args)]
+struct DiffOptions {
+    /// Only report problems on lines changed since the git revision, and in untracked files
+    #[arg(long, value_name = "REV", conflicts_with = "stdin")]
+    diff_base: Option<String>,
+    /// Only report problems on lines changed in the git index
+    #[arg(long, conflicts_with = "stdin")]
+    staged: bool,
+}
+
+impl DiffOptions {
+    fn changed_lines(&self) -> anyhow::Result<Option<ChangedLines>> {
+        if self.diff_base.is_none() && !self.staged {
+            return Ok(None);
+        }
+        ChangedLines::from_git(self.diff_base.as_deref(), self.staged).map(Some)
+    }
+}
+
+#[derive(Args)]
+struct BaselineOptions {
+    /// Record the current problems to the file instead of reporting them
+    #[arg(long, value_name = "FILE", conflicts_with = "baseline")]
+    write_baseline: Option<PathBuf>,
+    /// Do not report problems recorded by --write-baseline. Entries which no longer match are listed
+    #[arg(long, value_name = "FILE")]
+    baseline: Option<PathBuf>,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum OutputFormat {
+    /// Source snippets with explanations
+    Human,
+    /// A line per diagnosis
+    Text,
+    Json,
+    /// JSON Lines
+    Jsonl,
+    /// SARIF 2.1.0
+    Sarif,
+    /// GitHub Actions workflow commands
+    Github,
+    /// Checkstyle XML
+    Checkstyle,
+    /// JUnit XML
+    Junit,
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+enum ColorChoice {
+    /// Colorize if stdout is a terminal and NO_COLOR is not set
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Auto => {
+                std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Command {
     Check {
-        #[arg(index(1))]
-        file_path: PathBuf,
+        #[arg(index(1), required_unless_present("stdin"))]
+        file_paths: Vec<PathBuf>,
+        #[command(flatten)]
+        walk: WalkOptions,
+        #[command(flatten)]
+        stdin: StdinOptions,
+        #[command(flatten)]
+        resolve: ResolveOptions,
+        #[command(flatten)]
+        diff: DiffOptions,
+        #[command(flatten)]
+        baseline: BaselineOptions,
+        #[arg(long, value_enum, default_value_t = OutputFormat::Human)]
+        format: OutputFormat,
+        #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+        color: ColorChoice,
+        /// Number of files checked in parallel. Defaults to the number of available cores
+        #[arg(short, long, value_name = "N")]
+        jobs: Option<NonZeroUsize>,
+    },
+    /// Fix files in place. With --stdin, the fixed content is written to stdout
+    Fix {
+        #[arg(index(1), required_unless_present("stdin"))]
+        file_paths: Vec<PathBuf>,
+        #[command(flatten)]
+        walk: WalkOptions,
+        #[command(flatten)]
+        stdin: StdinOptions,
+        #[command(flatten)]
+        resolve: ResolveOptions,
     },
-    Fix {},
     ShowConfig {
         #[arg(index(1))]
         file_path: PathBuf,
+        #[command(flatten)]
+        resolve: ResolveOptions,
+    },
+    /// Run a language server over stdio
+    Lsp {
+        #[command(flatten)]
+        resolve: ResolveOptions,
     },
 }
 
-fn main() -> anyhow::Result<()> {
+const IGNORE_FILENAME: &str = ".editorconfig-lintignore";
+
+/// Globs relative to `root`. Each glob is negated if `exclude`.
+fn build_overrides(
+    root: &Path,
+    globs: &[String],
+    exclude: bool,
+) -> anyhow::Result<ignore::overrides::Override> {
+    let mut builder = ignore::overrides::OverrideBuilder::new(root);
+    for glob in globs {
+        if exclude {
+            builder.add(&format!("!{glob}"))?;
+        } else {
+            builder.add(glob)?;
+        }
+    }
+    Ok(builder.build()?)
+}
+
+/// Expand directories in `paths` recursively. Files are visited in path order.
+/// `--include` and `--exclude` globs are relative to each of `paths`,
+/// or to the parent directory if it is a file.
+fn collect_files(paths: &[PathBuf], options: &WalkOptions) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for path in paths {
+        let root = match path.parent() {
+            Some(parent) if !path.is_dir() => parent,
+            _ => path,
+        };
+        let includes = build_overrides(root, &options.include, false)?;
+        let excludes = build_overrides(root, &options.exclude, true)?;
+
+        let mut builder = ignore::WalkBuilder::new(path);
+        builder
+            .hidden(false)
+            .overrides(excludes)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .filter_entry(|entry| entry.file_name() != ".git");
+        if options.no_ignore {
+            builder.standard_filters(false);
+        } else {
+            builder.add_custom_ignore_filename(IGNORE_FILENAME);
+        }
+
+        for entry in builder.build() {
+            let entry = entry?;
+            let is_file = entry.file_type().map(|t| !t.is_dir()).unwrap_or(false);
+            if is_file
+                && (includes.is_empty() || includes.matched(entry.path(), false).is_whitelist())
+            {
+                files.push(entry.into_path());
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Resolve config for `path`. Returns `None` if no section matches.
+fn get_config_for(resolver: &ConfigResolver, path: &Path) -> Result<Option<Config>, Error> {
+    match resolver.get_config_for(path) {
+        Ok(config) => Ok(Some(config)),
+        Err(Error::NotFound) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Number of leading bytes searched for NUL, the same as git
+const BINARY_PROBE_LEN: usize = 8000;
+
+/// Whether the file starting with `head` is binary, so that it should not be checked or fixed.
+/// UTF-16 text is not binary though it contains NUL.
+fn is_binary(head: &[u8], config: &Config) -> bool {
+    !matches!(
+        config.charset,
+        Some(Charset::Utf16BigEndian) | Some(Charset::Utf16LittleEndian)
+    ) && head[..head.len().min(BINARY_PROBE_LEN)].contains(&0)
+}
+
+type CheckResult = anyhow::Result<Option<(Config, Vec<Diagnosis>)>>;
+
+/// Check `file_path`. Returns `None` if no section matches or the file is binary.
+fn check_file(resolver: &ConfigResolver, file_path: &Path) -> CheckResult {
+    let config = match get_config_for(resolver, file_path)? {
+        Some(config) => config,
+        None => return Ok(None),
+    };
+    let mut file = std::fs::File::open(file_path)?;
+    let mut head = Vec::with_capacity(BINARY_PROBE_LEN);
+    (&mut file)
+        .take(BINARY_PROBE_LEN as u64)
+        .read_to_end(&mut head)?;
+    if is_binary(&head, &config) {
+        return Ok(None);
+    }
+    let reader = BufReader::new(head.as_slice().chain(file));
+    let diagnoses = check(reader, config.clone())?;
+    Ok(Some((config, diagnoses)))
+}
+
+/// Check `content` as if it is at `file_path`. Returns `None` if it is binary.
+fn check_content(resolver: &ConfigResolver, file_path: &Path, content: &[u8]) -> CheckResult {
+    let config = match get_config_for(resolver, file_path)? {
+        Some(config) => config,
+        None => return Ok(None),
+    };
+    if is_binary(content, &config) {
+        return Ok(None);
+    }
+    let diagnoses = check(content, config.clone())?;
+    Ok(Some((config, diagnoses)))
+}
+
+/// Check staged content of `file_path` instead of the working tree.
+/// The content is returned so that it is reported instead of the working tree.
+fn check_staged_file(
+    resolver: &ConfigResolver,
+    changed_lines: &ChangedLines,
+    file_path: &Path,
+) -> (Option<Vec<u8>>, CheckResult) {
+    match changed_lines.staged_content(file_path) {
+        Ok(content) => {
+            let result = check_content(resolver, file_path, &content);
+            (Some(content), result)
+        }
+        Err(e) => (None, Err(e)),
+    }
+}
+
+/// Check `files` with `jobs` threads. `on_result` is called in the order of `files`
+/// with the checked content if it is not the file on disk.
+/// With `staged`, content in the index is checked.
+fn check_files(
+    files: &[PathBuf],
+    jobs: NonZeroUsize,
+    resolver: &ConfigResolver,
+    staged: Option<&ChangedLines>,
+    mut on_result: impl FnMut(&Path, Option<&[u8]>, CheckResult) -> std::io::Result<()>,
+) -> std::io::Result<()> {
+    let next_index = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..jobs.get().min(files.len()) {
+            let sender = sender.clone();
+            let next_index = &next_index;
+            scope.spawn(move || loop {
+                let index = next_index.fetch_add(1, Ordering::Relaxed);
+                let Some(file_path) = files.get(index) else {
+                    break;
+                };
+                let result = match staged {
+                    Some(changed_lines) => check_staged_file(resolver, changed_lines, file_path),
+                    None => (None, check_file(resolver, file_path)),
+                };
+                if sender.send((index, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        // results arrive out of order. hold them until preceding files are done
+        let mut pending = BTreeMap::new();
+        let mut emitted = 0;
+        for (index, result) in receiver {
+            pending.insert(index, result);
+            while let Some((content, result)) = pending.remove(&emitted) {
+                let result = on_result(&files[emitted], content.as_deref(), result);
+                if result.is_err() {
+                    // stop workers
+                    next_index.store(files.len(), Ordering::Relaxed);
+                    return result;
+                }
+                emitted += 1;
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Write `content` to a temporary file next to `path` and replace `path` with it.
+/// If `path` is a symlink, its target is replaced.
+fn write_atomic(path: &Path, content: &[u8]) -> std::io::Result<()> {
+    let path = &path.canonicalize()?;
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = path.with_file_name(format!(".{file_name}.editorconfig-lint.tmp"));
+    let result = (|| {
+        let mut file = std::fs::File::create(&temp_path)?;
+        file.write_all(content)?;
+        file.sync_all()?;
+        file.set_permissions(std::fs::metadata(path)?.permissions())?;
+        std::fs::rename(&temp_path, path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+fn fix_file(resolver: &ConfigResolver, file_path: &Path) -> anyhow::Result<bool> {
+    let config = match get_config_for(resolver, file_path)? {
+        Some(config) => config,
+        None => return Ok(false),
+    };
+    let content = std::fs::read(file_path)?;
+    if is_binary(&content, &config) || check(&content[..], config.clone())?.is_empty() {
+        return Ok(false);
+    }
+
+    let fixed = fix(&content[..], Vec::with_capacity(content.len()), &config)?;
+    if fixed == content {
+        return Ok(false);
+    }
+    write_atomic(file_path, &fixed)?;
+
+    Ok(true)
+}
+
+/// Write fixed `content` to stdout as if it is at `file_path`
+fn fix_content(resolver: &ConfigResolver, file_path: &Path, content: &[u8]) -> anyhow::Result<()> {
+    let mut stdout = std::io::stdout().lock();
+    match get_config_for(resolver, file_path)? {
+        Some(config) => {
+            fix(content, &mut stdout, &config)?;
+        }
+        None => stdout.write_all(content)?,
+    }
+    stdout.flush()?;
+    Ok(())
+}
+
+fn main() -> anyhow::Result<ExitCode> {
     let cli = Cli::try_parse()?;
 
     match cli.command {
-        Command::ShowConfig { file_path } => {
-            println!("{:#?}", Config::get_config_for(&file_path)?);
+        Command::ShowConfig { file_path, resolve } => {
+            println!(
+                "{:#?}",
+                Config::get_config_for_with(&file_path, resolve.resolution())?
+            );
+        }
+        Command::Lsp { resolve } => lsp::run(resolve.resolution())?,
+        Command::Check {
+            file_paths,
+            walk,
+            stdin,
+            resolve,
+            diff,
+            baseline: baseline_options,
+            format,
+            color,
+            jobs,
+        } => {
+            let stdout = std::io::stdout().lock();
+            let mut reporter: Box<dyn Reporter> = match format {
+                OutputFormat::Human => Box::new(HumanReporter::new(stdout, color.enabled())),
+                OutputFormat::Text => Box::new(TextReporter(stdout)),
+                OutputFormat::Json => Box::new(JsonReporter::new(stdout)),
+                OutputFormat::Jsonl => Box::new(JsonLinesReporter(stdout)),
+                OutputFormat::Sarif => Box::new(SarifReporter::new(stdout)),
+                OutputFormat::Github => Box::new(GithubReporter(stdout)),
+                OutputFormat::Checkstyle => Box::new(CheckstyleReporter::new(stdout)),
+                OutputFormat::Junit => Box::new(JunitReporter::new(stdout)),
+            };
+            let jobs = jobs
+                .or_else(|| std::thread::available_parallelism().ok())
+                .unwrap_or(NonZeroUsize::MIN);
+            let resolver = ConfigResolver::with_path_resolution(resolve.resolution());
+            let changed_lines = diff.changed_lines()?;
+            let mut baseline = match &baseline_options.baseline {
+                Some(path) => Some(
+                    Baseline::open(path)
+                        .with_context(|| format!("failed to read {}", path.display()))?,
+                ),
+                None => None,
+            };
+            let mut new_baseline = match &baseline_options.write_baseline {
+                Some(path) => Some(
+                    Baseline::new(path)
+                        .with_context(|| format!("failed to resolve {}", path.display()))?,
+                ),
+                None => None,
+            };
+            let mut summary = Summary::default();
+            let mut on_result =
+                |file_path: &Path, source: Option<&[u8]>, result: CheckResult| match result {
+                    Ok(Some((config, mut diagnoses))) => {
+                        if let Some(changed_lines) = &changed_lines {
+                            changed_lines.retain(file_path, &mut diagnoses);
+                        }
+                        if baseline.is_some() || new_baseline.is_some() {
+                            let content = match source {
+                                Some(source) => source.to_vec(),
+                                None if !diagnoses.is_empty() => std::fs::read(file_path)?,
+                                None => Vec::new(),
+                            };
+                            if let Some(baseline) = &mut baseline {
+                                baseline.suppress(
+                                    file_path,
+                                    &content,
+                                    config.charset,
+                                    &mut diagnoses,
+                                )?;
+                            }
+                            if let Some(new_baseline) = &mut new_baseline {
+                                new_baseline.add(
+                                    file_path,
+                                    &content,
+                                    config.charset,
+                                    &diagnoses,
+                                )?;
+                                diagnoses.clear();
+                            }
+                        }
+                        summary.checked_files += 1;
+                        if !diagnoses.is_empty() {
+                            summary.failed_files += 1;
+                            summary.problems += diagnoses.len();
+                        }
+                        match source {
+                            Some(source) => {
+                                reporter.report_source(file_path, source, &config, &diagnoses)
+                            }
+                            None => reporter.report(file_path, &config, &diagnoses),
+                        }
+                    }
+                    Ok(None) => Ok(()),
+                    Err(e) => {
+                        summary.failed_files += 1;
+                        reporter.report_error(file_path, &e)
+                    }
+                };
+            if let Some((file_path, content)) = stdin.read()? {
+                let result = check_content(&resolver, file_path, &content);
+                on_result(file_path, Some(&content), result)?;
+            } else {
+                let mut files = collect_files(&file_paths, &walk)?;
+                if let Some(changed_lines) = &changed_lines {
+                    files.retain(|file_path| changed_lines.contains_file(file_path));
+                }
+                let staged = changed_lines.as_ref().filter(|_| diff.staged);
+                check_files(&files, jobs, &resolver, staged, &mut on_result)?;
+            }
+            reporter.finish(&summary)?;
+            if let (Some(path), Some(new_baseline)) =
+                (&baseline_options.write_baseline, &new_baseline)
+            {
+                new_baseline.save(path)?;
+                eprintln!(
+                    "recorded {} problem(s) to {}",
+                    new_baseline.len(),
+                    path.display()
+                );
+            }
+            if let Some(baseline) = &baseline {
+                let stale = baseline.stale();
+                for (file, entry) in &stale {
+                    eprintln!("stale baseline entry: {file}: {}", entry.reason);
+                }
+                if !stale.is_empty() {
+                    eprintln!(
+                        "{} baseline entry(s) no longer match. Update the baseline with --write-baseline",
+                        stale.len()
+                    );
+                }
+            }
+            if summary.failed_files != 0 {
+                return Ok(ExitCode::FAILURE);
+            }
         }
-        Command::Check { file_path } => {
-            let config = Config::get_config_for(&file_path)?;
-            let reader = BufReader::new(std::fs::File::open(&file_path)?);
-            let diagnoses = check(reader, config)?;
-            let mut stdout = std::io::stdout().lock();
-            for diagnosis in diagnoses {
-                diagnosis.fmt(&mut stdout, &file_path.display())?;
+        Command::Fix {
+            file_paths,
+            walk,
+            stdin,
+            resolve,
+        } => {
+            let resolver = ConfigResolver::with_path_resolution(resolve.resolution());
+            if let Some((file_path, content)) = stdin.read()? {
+                fix_content(&resolver, file_path, &content)?;
+                return Ok(ExitCode::SUCCESS);
+            }
+            for file_path in collect_files(&file_paths, &walk)? {
+                if fix_file(&resolver, &file_path)? {
+                    println!("fixed: {}", file_path.display());
+                }
             }
         }
-        Command::Fix {} => todo!(),
     }
 
-    Ok(())
+    Ok(ExitCode::SUCCESS)
 }
@@ -0,0 +1,245 @@
+use std::path::{Component, Path};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Char(char),
+    /// `?`. Any character except `/`
+    AnyChar,
+    /// `*`. Any string which does not contain `/`
+    AnySegment,
+    /// `**`. Any string
+    AnyPath,
+    /// `[abc]`, `[a-z]` or `[!abc]`
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+    /// `{a,b,c}`
+    Alternatives(Vec<Vec<Token>>),
+    /// `{n..m}`. An integer between `n` and `m` without leading zeros
+    NumberRange(i64, i64),
+}
+
+/// Section name of `.editorconfig`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    tokens: Vec<Token>,
+}
+
+/// Length of `{...}` content which starts at `chars[0]`. `None` if there is no matching `}`.
+fn find_closing_brace(chars: &[char]) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// `[+-]?[0-9]+`
+fn parse_number(s: &str) -> Option<i64> {
+    let digits = s.strip_prefix(['+', '-']).unwrap_or(s);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    s.parse().ok()
+}
+
+/// Content of `{...}`
+fn parse_braces(chars: &[char]) -> Vec<Token> {
+    // split with top level commas
+    let mut alternatives = Vec::new();
+    let mut depth = 0;
+    let mut begin = 0;
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                alternatives.push(parse(&chars[begin..i]));
+                begin = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+
+    if alternatives.is_empty() {
+        let inner: String = chars.iter().collect();
+        if let Some((n, m)) = inner.split_once("..") {
+            if let (Some(n), Some(m)) = (parse_number(n), parse_number(m)) {
+                return vec![Token::NumberRange(n.min(m), n.max(m))];
+            }
+        }
+        // braces without comma are not special
+        let mut tokens = vec![Token::Char('{')];
+        tokens.extend(parse(chars));
+        tokens.push(Token::Char('}'));
+        return tokens;
+    }
+    alternatives.push(parse(&chars[begin..]));
+    vec![Token::Alternatives(alternatives)]
+}
+
+/// Content of `[...]` which starts at `chars[0]` and its length including `]`.
+/// `None` if there is no matching `]` or it contains `/`.
+fn parse_class(chars: &[char]) -> Option<(Token, usize)> {
+    let negated = matches!(chars.first(), Some('!' | '^'));
+    let mut ranges = Vec::new();
+    let mut i = usize::from(negated);
+    loop {
+        let mut ch = *chars.get(i)?;
+        match ch {
+            ']' => return Some((Token::Class { negated, ranges }, i + 1)),
+            '/' => return None,
+            '\\' => {
+                i += 1;
+                ch = *chars.get(i)?;
+            }
+            _ => {}
+        }
+        match (chars.get(i + 1), chars.get(i + 2)) {
+            (Some('-'), Some(&end)) if end != ']' && end != '/' => {
+                ranges.push((ch, end));
+                i += 3;
+            }
+            _ => {
+                ranges.push((ch, ch));
+                i += 1;
+            }
+        }
+    }
+}
+
+fn parse(chars: &[char]) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let rest = &chars[i + 1..];
+        let (token, len) = match chars[i] {
+            '\\' if !rest.is_empty() => (Token::Char(rest[0]), 2),
+            '*' if rest.first() == Some(&'*') => (Token::AnyPath, 2),
+            '*' => (Token::AnySegment, 1),
+            '?' => (Token::AnyChar, 1),
+            '[' => match parse_class(rest) {
+                Some((token, len)) => (token, len + 1),
+                None => (Token::Char('['), 1),
+            },
+            '{' => match find_closing_brace(rest) {
+                Some(len) => {
+                    tokens.extend(parse_braces(&rest[..len]));
+                    i += len + 2;
+                    continue;
+                }
+                None => (Token::Char('{'), 1),
+            },
+            // `/**/` matches zero or more directories
+            '/' if rest.starts_with(&['*', '*', '/']) => (
+                Token::Alternatives(vec![
+                    vec![Token::Char('/')],
+                    vec![Token::Char('/'), Token::AnyPath, Token::Char('/')],
+                ]),
+                4,
+            ),
+            ch => (Token::Char(ch), 1),
+        };
+        tokens.push(token);
+        i += len;
+    }
+    tokens
+}
+
+/// Match `tokens` against the beginning of `text` and pass the rest to `next`.
+fn match_tokens(tokens: &[Token], text: &str, next: &dyn Fn(&str) -> bool) -> bool {
+    let Some((token, tokens)) = tokens.split_first() else {
+        return next(text);
+    };
+    let then = |text: &str| match_tokens(tokens, text, next);
+    let first = text.chars().next();
+    let after_first = || &text[first.map(char::len_utf8).unwrap_or(0)..];
+    match token {
+        Token::Char(ch) => first == Some(*ch) && then(after_first()),
+        Token::AnyChar => matches!(first, Some(ch) if ch != '/') && then(after_first()),
+        Token::Class { negated, ranges } => match first {
+            Some(ch) if ch != '/' => {
+                let contained = ranges
+                    .iter()
+                    .any(|(begin, end)| (*begin..=*end).contains(&ch));
+                contained != *negated && then(after_first())
+            }
+            _ => false,
+        },
+        Token::AnySegment => {
+            let end = text.find('/').unwrap_or(text.len());
+            (0..=end)
+                .filter(|i| text.is_char_boundary(*i))
+                .any(|i| then(&text[i..]))
+        }
+        Token::AnyPath => (0..=text.len())
+            .filter(|i| text.is_char_boundary(*i))
+            .any(|i| then(&text[i..])),
+        Token::Alternatives(alternatives) => alternatives
+            .iter()
+            .any(|alternative| match_tokens(alternative, text, &then)),
+        Token::NumberRange(min, max) => {
+            let sign = usize::from(text.starts_with(['+', '-']));
+            let digits = text[sign..]
+                .bytes()
+                .take_while(|b| b.is_ascii_digit())
+                .count();
+            (1..=digits).rev().any(|len| {
+                let number = &text[..sign + len];
+                if len > 1 && text.as_bytes()[sign] == b'0' {
+                    return false;
+                }
+                match number.parse::<i64>() {
+                    Ok(number) => (*min..=*max).contains(&number) && then(&text[sign + len..]),
+                    Err(_) => false,
+                }
+            })
+        }
+    }
+}
+
+impl Pattern {
+    /// Compile a section name.
+    /// A pattern which contains `/` is relative to the directory of `.editorconfig`.
+    /// Otherwise it matches files in any depth.
+    pub fn new(section: &str) -> Pattern {
+        let pattern = if section.contains('/') {
+            format!("/{}", section.strip_prefix('/').unwrap_or(section))
+        } else {
+            format!("/**/{section}")
+        };
+        let chars: Vec<char> = pattern.chars().collect();
+        Pattern {
+            tokens: parse(&chars),
+        }
+    }
+
+    /// Test `path` which is relative to the directory of `.editorconfig`.
+    pub fn matches_path(&self, path: &Path) -> bool {
+        let mut text = String::new();
+        for component in path.components() {
+            if let Component::Normal(name) = component {
+                text.push('/');
+                text.push_str(&name.to_string_lossy());
+            }
+        }
+        self.matches(&text)
+    }
+
+    /// Test `path` which is `/` separated and starts with `/`.
+    pub fn matches(&self, path: &str) -> bool {
+        match_tokens(&self.tokens, path, &|rest| rest.is_empty())
+    }
+}
@@ -8,12 +8,12 @@ pub enum NewLineChar {
     Lf,
 }
 
-impl PartialEq<LineEnding> for NewLineChar {
-    fn eq(&self, other: &LineEnding) -> bool {
-        matches!(
-            (self, other),
-            (NewLineChar::Cr, LineEnding::Cr) | (NewLineChar::Lf, LineEnding::Lf)
-        )
+impl From<NewLineChar> for LineEnding {
+    fn from(ch: NewLineChar) -> Self {
+        match ch {
+            NewLineChar::Cr => LineEnding::Cr,
+            NewLineChar::Lf => LineEnding::Lf,
+        }
     }
 }
 
@@ -42,10 +42,20 @@ impl From<&[u8]> for CharByteArray {
     }
 }
 
+impl CharByteArray {
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buffer[0..(self.len as usize)]
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Character {
     Bom,
     Invalid(CharByteArray),
+    /// BOM of the other byte order of UTF-16
+    ReversedBom,
+    /// Character which is valid but not printable, like C1 control characters of Latin-1
+    Control(CharByteArray),
     NewLine(NewLineChar),
     Indent(IndentChar),
     Valid(CharByteArray),
@@ -54,151 +64,257 @@ pub enum Character {
 #[enum_dispatch]
 pub trait Reader {
     fn next(&mut self) -> std::io::Result<Option<Character>>;
+
+    /// Pass the following run of printable ASCII characters except space to `sink` without
+    /// decoding them one by one. Returns the number of characters in the run.
+    fn read_ascii_run(
+        &mut self,
+        _sink: &mut dyn FnMut(&[u8]) -> std::io::Result<()>,
+    ) -> std::io::Result<usize> {
+        Ok(0)
+    }
 }
 
-pub struct Utf8Reader<T: std::io::Read + Sized>(T);
-impl<T: std::io::Read + Sized> Reader for Utf8Reader<T> {
-    fn next(&mut self) -> std::io::Result<Option<Character>> {
-        let mut buf: [u8; 4] = [0; 4];
-        let len = self.0.read(&mut buf[0..1])?;
-        if len == 0 {
-            Ok(None)
+fn is_printable_ascii(byte: &u8) -> bool {
+    (0x21..=0x7E).contains(byte)
+}
+
+/// Bytes of `T` which are scanned from `fill_buf` slices.
+/// A character split across slices is gathered into `carry`.
+pub struct ByteSource<T: std::io::BufRead + Sized> {
+    inner: T,
+    carry: [u8; 4],
+    carry_len: usize,
+}
+
+impl<T: std::io::BufRead + Sized> ByteSource<T> {
+    pub fn new(inner: T) -> Self {
+        ByteSource {
+            inner,
+            carry: [0; 4],
+            carry_len: 0,
+        }
+    }
+
+    /// Returns at least `n` bytes unless the input ends. `n` must not exceed 4.
+    fn peek(&mut self, n: usize) -> std::io::Result<&[u8]> {
+        if self.carry_len == 0 {
+            let buf = self.inner.fill_buf()?;
+            if buf.len() >= n || buf.is_empty() {
+                // borrow checker can't see that `buf` is not used in other branch
+                return self.inner.fill_buf();
+            }
+        }
+        while self.carry_len < n {
+            let buf = self.inner.fill_buf()?;
+            if buf.is_empty() {
+                break;
+            }
+            let len = buf.len().min(n - self.carry_len);
+            self.carry[self.carry_len..(self.carry_len + len)].copy_from_slice(&buf[..len]);
+            self.carry_len += len;
+            self.inner.consume(len);
+        }
+        Ok(&self.carry[..self.carry_len])
+    }
+
+    /// Consume `n` bytes of the last `peek`
+    fn consume(&mut self, n: usize) {
+        if self.carry_len == 0 {
+            self.inner.consume(n);
         } else {
-            match buf[0] {
-                b'\r' => Ok(Some(Character::NewLine(NewLineChar::Cr))),
-                b'\n' => Ok(Some(Character::NewLine(NewLineChar::Lf))),
-                b' ' => Ok(Some(Character::Indent(IndentChar::Space))),
-                b'\t' => Ok(Some(Character::Indent(IndentChar::Tab))),
-                ch if ch < 0x80 => Ok(Some(Character::Valid(buf[0..1].into()))),
-                ch => {
-                    // read more chars
-                    let n = if ch & 0xF8 == 0xF0 {
-                        4
-                    } else if ch & 0xF0 == 0xE0 {
-                        3
-                    } else if ch & 0xE0 == 0xC0 {
-                        2
-                    } else {
-                        return Ok(Some(Character::Invalid(buf[0..1].into())));
-                    };
-
-                    let len = self.0.read(&mut buf[1..n])?;
-                    if len == n {
-                        if let Ok(ch) = std::str::from_utf8(&buf[0..n]) {
-                            return if ch == "\u{FEFF}" {
-                                Ok(Some(Character::Bom))
-                            } else {
-                                Ok(Some(Character::Valid(buf[0..n].into())))
-                            };
-                        }
-                    }
-
-                    Ok(Some(Character::Invalid(buf[0..len].into())))
-                }
+            self.carry.copy_within(n..self.carry_len, 0);
+            self.carry_len -= n;
+        }
+    }
+
+    fn read_ascii_run(
+        &mut self,
+        sink: &mut dyn FnMut(&[u8]) -> std::io::Result<()>,
+    ) -> std::io::Result<usize> {
+        if self.carry_len != 0 {
+            return Ok(0);
+        }
+        let mut total = 0;
+        loop {
+            let buf = self.inner.fill_buf()?;
+            let len = buf
+                .iter()
+                .position(|b| !is_printable_ascii(b))
+                .unwrap_or(buf.len());
+            if len == 0 {
+                return Ok(total);
+            }
+            sink(&buf[..len])?;
+            let reached_end = len < buf.len();
+            self.inner.consume(len);
+            total += len;
+            if reached_end {
+                return Ok(total);
             }
         }
     }
 }
 
-pub struct Latin1Reader<T: std::io::Read + Sized>(T);
-impl<T: std::io::Read + Sized> Reader for Latin1Reader<T> {
+fn ascii_character(byte: u8) -> Option<Character> {
+    match byte {
+        b'\r' => Some(Character::NewLine(NewLineChar::Cr)),
+        b'\n' => Some(Character::NewLine(NewLineChar::Lf)),
+        b' ' => Some(Character::Indent(IndentChar::Space)),
+        b'\t' => Some(Character::Indent(IndentChar::Tab)),
+        _ => None,
+    }
+}
+
+pub struct Utf8Reader<T: std::io::BufRead + Sized>(ByteSource<T>);
+impl<T: std::io::BufRead + Sized> Reader for Utf8Reader<T> {
     fn next(&mut self) -> std::io::Result<Option<Character>> {
-        let mut buf: [u8; 1] = [0; 1];
-        let len = self.0.read(&mut buf[..])?;
-        if len == 0 {
-            Ok(None)
+        let lead = match self.0.peek(1)?.first() {
+            None => return Ok(None),
+            Some(&lead) => lead,
+        };
+        if lead < 0x80 {
+            self.0.consume(1);
+            return Ok(Some(
+                ascii_character(lead).unwrap_or_else(|| Character::Valid([lead][..].into())),
+            ));
+        }
+
+        let n = if lead & 0xF8 == 0xF0 {
+            4
+        } else if lead & 0xF0 == 0xE0 {
+            3
+        } else if lead & 0xE0 == 0xC0 {
+            2
         } else {
-            match buf[0] {
-                b'\r' => Ok(Some(Character::NewLine(NewLineChar::Cr))),
-                b'\n' => Ok(Some(Character::NewLine(NewLineChar::Lf))),
-                b' ' => Ok(Some(Character::Indent(IndentChar::Space))),
-                b'\t' => Ok(Some(Character::Indent(IndentChar::Tab))),
-                ch if !(0x7F..=0xA0).contains(&ch) => Ok(Some(Character::Valid(buf[0..1].into()))),
-                _ => Ok(Some(Character::Invalid(buf[0..1].into()))),
+            self.0.consume(1);
+            return Ok(Some(Character::Invalid([lead][..].into())));
+        };
+
+        let buf = self.0.peek(n)?;
+        let buf = &buf[..n.min(buf.len())];
+        let (ch, len) = match std::str::from_utf8(buf) {
+            Ok("\u{FEFF}") => (Character::Bom, n),
+            Ok(_) => (Character::Valid(buf.into()), n),
+            Err(e) => {
+                // invalid sequence ends before the first byte which can't be a part of it
+                let len = e.error_len().unwrap_or(buf.len()).max(1);
+                (Character::Invalid(buf[..len].into()), len)
             }
-        }
+        };
+        self.0.consume(len);
+        Ok(Some(ch))
+    }
+
+    fn read_ascii_run(
+        &mut self,
+        sink: &mut dyn FnMut(&[u8]) -> std::io::Result<()>,
+    ) -> std::io::Result<usize> {
+        self.0.read_ascii_run(sink)
     }
 }
 
-pub struct Utf16LeReader<T: std::io::Read + Sized>(T);
-impl<T: std::io::Read + Sized> Reader for Utf16LeReader<T> {
+pub struct Latin1Reader<T: std::io::BufRead + Sized>(ByteSource<T>);
+impl<T: std::io::BufRead + Sized> Reader for Latin1Reader<T> {
     fn next(&mut self) -> std::io::Result<Option<Character>> {
-        let mut buf: [u8; 4] = [0; 4];
-        let len = self.0.read(&mut buf[0..2])?;
-        match len {
-            0 => Ok(None),
-            1 => Ok(Some(Character::Invalid(buf[0..1].into()))),
-            2 => match buf[0..2] {
-                [b'\r', 0x00_u8] => Ok(Some(Character::NewLine(NewLineChar::Cr))),
-                [b'\n', 0x00_u8] => Ok(Some(Character::NewLine(NewLineChar::Lf))),
-                [b' ', 0x00_u8] => Ok(Some(Character::Indent(IndentChar::Space))),
-                [b'\t', 0x00_u8] => Ok(Some(Character::Indent(IndentChar::Tab))),
-                [0xFF_u8, 0xFE_u8] => Ok(Some(Character::Bom)),
-                [_, ch] if (0xD8..=0xDB).contains(&ch) => {
-                    let len = self.0.read(&mut buf[2..4])?;
-                    if len == 2 && (0xDC..=0xDF).contains(&buf[3]) {
-                        Ok(Some(Character::Valid(buf[0..4].into())))
-                    } else {
-                        Ok(Some(Character::Invalid(buf[0..(2 + len)].into())))
-                    }
-                }
-                _ => Ok(Some(Character::Valid(buf[0..1].into()))),
-            },
-            _ => unreachable!("Not available"),
+        let ch = match self.0.peek(1)?.first() {
+            None => return Ok(None),
+            Some(&ch) => ch,
+        };
+        self.0.consume(1);
+        Ok(Some(match ascii_character(ch) {
+            Some(ch) => ch,
+            None if (0x7F..=0xA0).contains(&ch) => Character::Control([ch][..].into()),
+            None => Character::Valid([ch][..].into()),
+        }))
+    }
+
+    fn read_ascii_run(
+        &mut self,
+        sink: &mut dyn FnMut(&[u8]) -> std::io::Result<()>,
+    ) -> std::io::Result<usize> {
+        self.0.read_ascii_run(sink)
+    }
+}
+
+pub struct Utf16Reader<T: std::io::BufRead + Sized, const BIG_ENDIAN: bool>(ByteSource<T>);
+pub type Utf16LeReader<T> = Utf16Reader<T, false>;
+pub type Utf16BeReader<T> = Utf16Reader<T, true>;
+
+impl<T: std::io::BufRead + Sized, const BIG_ENDIAN: bool> Utf16Reader<T, BIG_ENDIAN> {
+    fn to_unit(bytes: &[u8]) -> u16 {
+        if BIG_ENDIAN {
+            u16::from_be_bytes([bytes[0], bytes[1]])
+        } else {
+            u16::from_le_bytes([bytes[0], bytes[1]])
         }
     }
 }
-pub struct Utf16BeReader<T: std::io::Read + Sized>(T);
-impl<T: std::io::Read + Sized> Reader for Utf16BeReader<T> {
+
+impl<T: std::io::BufRead + Sized, const BIG_ENDIAN: bool> Reader for Utf16Reader<T, BIG_ENDIAN> {
     fn next(&mut self) -> std::io::Result<Option<Character>> {
-        let mut buf: [u8; 4] = [0; 4];
-        let len = self.0.read(&mut buf[0..2])?;
-        match len {
-            0 => Ok(None),
-            1 => Ok(Some(Character::Invalid(buf[0..1].into()))),
-            2 => match buf[0..2] {
-                [0x00_u8, b'\r'] => Ok(Some(Character::NewLine(NewLineChar::Cr))),
-                [0x00_u8, b'\n'] => Ok(Some(Character::NewLine(NewLineChar::Lf))),
-                [0x00_u8, b' '] => Ok(Some(Character::Indent(IndentChar::Space))),
-                [0x00_u8, b'\t'] => Ok(Some(Character::Indent(IndentChar::Tab))),
-                [0xFE_u8, 0xFF_u8] => Ok(Some(Character::Bom)),
-                [ch, _] if (0xD8..=0xDB).contains(&ch) => {
-                    let len = self.0.read(&mut buf[2..4])?;
-                    if len == 2 && (0xDC..=0xDF).contains(&buf[3]) {
-                        Ok(Some(Character::Valid(buf[0..4].into())))
-                    } else {
-                        Ok(Some(Character::Invalid(buf[0..(2 + len)].into())))
-                    }
-                }
-                _ => Ok(Some(Character::Valid(buf[0..1].into()))),
-            },
-            _ => unreachable!("Not available"),
+        let buf = self.0.peek(2)?;
+        match buf.len() {
+            0 => return Ok(None),
+            1 => {
+                let ch = Character::Invalid(buf[..1].into());
+                self.0.consume(1);
+                return Ok(Some(ch));
+            }
+            _ => {}
         }
+
+        let unit = Self::to_unit(buf);
+        let (ch, len) = match unit {
+            0xFEFF => (Character::Bom, 2),
+            0xFFFE => (Character::ReversedBom, 2),
+            0xD800..=0xDBFF => {
+                let buf = self.0.peek(4)?;
+                if buf.len() >= 4 && (0xDC00..=0xDFFF).contains(&Self::to_unit(&buf[2..4])) {
+                    (Character::Valid(buf[..4].into()), 4)
+                } else {
+                    // unpaired high surrogate. following unit is read again
+                    (Character::Invalid(buf[..2].into()), 2)
+                }
+            }
+            0xDC00..=0xDFFF => (Character::Invalid(buf[..2].into()), 2),
+            _ => (
+                u8::try_from(unit)
+                    .ok()
+                    .and_then(ascii_character)
+                    .unwrap_or_else(|| Character::Valid(buf[..2].into())),
+                2,
+            ),
+        };
+        self.0.consume(len);
+        Ok(Some(ch))
     }
 }
 
-pub struct UncheckedEncodingReader<T: std::io::Read + Sized>(T);
+pub struct UncheckedEncodingReader<T: std::io::BufRead + Sized>(ByteSource<T>);
 
-impl<T: std::io::Read + Sized> Reader for UncheckedEncodingReader<T> {
+impl<T: std::io::BufRead + Sized> Reader for UncheckedEncodingReader<T> {
     fn next(&mut self) -> std::io::Result<Option<Character>> {
-        let mut buf: [u8; 1] = [0; 1];
-        let len = self.0.read(&mut buf[..])?;
-        if len == 0 {
-            Ok(None)
-        } else {
-            match buf[0] {
-                b'\r' => Ok(Some(Character::NewLine(NewLineChar::Cr))),
-                b'\n' => Ok(Some(Character::NewLine(NewLineChar::Lf))),
-                b' ' => Ok(Some(Character::Indent(IndentChar::Space))),
-                b'\t' => Ok(Some(Character::Indent(IndentChar::Tab))),
-                _ => Ok(Some(Character::Valid(buf[0..1].into()))),
-            }
-        }
+        let ch = match self.0.peek(1)?.first() {
+            None => return Ok(None),
+            Some(&ch) => ch,
+        };
+        self.0.consume(1);
+        Ok(Some(
+            ascii_character(ch).unwrap_or_else(|| Character::Valid([ch][..].into())),
+        ))
+    }
+
+    fn read_ascii_run(
+        &mut self,
+        sink: &mut dyn FnMut(&[u8]) -> std::io::Result<()>,
+    ) -> std::io::Result<usize> {
+        self.0.read_ascii_run(sink)
     }
 }
 
 #[enum_dispatch(Reader)]
-pub enum CharacterReader<T: std::io::Read + Sized> {
+pub enum CharacterReader<T: std::io::BufRead + Sized> {
     Utf8(Utf8Reader<T>),
     Latin1(Latin1Reader<T>),
     Utf16Le(Utf16LeReader<T>),
@@ -206,16 +322,73 @@ pub enum CharacterReader<T: std::io::Read + Sized> {
     UncheckedEncoding(UncheckedEncodingReader<T>),
 }
 
-impl<T: std::io::Read + Sized> CharacterReader<T> {
+impl<T: std::io::BufRead + Sized> CharacterReader<T> {
     pub fn new(reader: T, charset: Option<Charset>) -> Self {
+        let source = ByteSource::new(reader);
         match charset {
-            Some(Charset::Latin1) => todo!(),
+            Some(Charset::Latin1) => CharacterReader::Latin1(Latin1Reader(source)),
             Some(Charset::Utf8) | Some(Charset::Utf8WithBom) => {
-                CharacterReader::Utf8(Utf8Reader(reader))
+                CharacterReader::Utf8(Utf8Reader(source))
+            }
+            Some(Charset::Utf16BigEndian) => CharacterReader::Utf16Be(Utf16Reader(source)),
+            Some(Charset::Utf16LittleEndian) => CharacterReader::Utf16Le(Utf16Reader(source)),
+            None => CharacterReader::UncheckedEncoding(UncheckedEncodingReader(source)),
+        }
+    }
+}
+
+/// Decode a [`Character::Valid`] character. Bytes of unchecked encoding are decoded as UTF-8.
+pub fn decode(bytes: &[u8], charset: Option<Charset>) -> char {
+    match charset {
+        Some(Charset::Latin1) => bytes[0] as char,
+        Some(Charset::Utf16LittleEndian) | Some(Charset::Utf16BigEndian) => {
+            let units = bytes.chunks(2).map(|unit| match charset {
+                Some(Charset::Utf16LittleEndian) => u16::from_le_bytes([unit[0], unit[1]]),
+                _ => u16::from_be_bytes([unit[0], unit[1]]),
+            });
+            char::decode_utf16(units)
+                .next()
+                .and_then(|ch| ch.ok())
+                .unwrap_or(char::REPLACEMENT_CHARACTER)
+        }
+        _ => std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|s| s.chars().next())
+            .unwrap_or(char::REPLACEMENT_CHARACTER),
+    }
+}
+
+/// Split `input` into lines in the same way as [`crate::check`] counts lines.
+/// Each line keeps its newline characters. Leading BOM is skipped if `charset` expects it.
+pub fn read_lines<T: std::io::BufRead + Sized>(
+    input: T,
+    charset: Option<Charset>,
+) -> std::io::Result<Vec<Vec<Character>>> {
+    let mut reader = CharacterReader::new(input, charset);
+    let mut lines = vec![Vec::new()];
+    let mut first = true;
+    while let Some(ch) = reader.next()? {
+        if std::mem::take(&mut first)
+            && matches!(ch, Character::Bom | Character::ReversedBom)
+            && !matches!(charset, Some(Charset::Utf8) | Some(Charset::Latin1) | None)
+        {
+            continue;
+        }
+        let line = lines.last_mut().unwrap();
+        match (line.last(), ch) {
+            (Some(Character::NewLine(NewLineChar::Cr)), Character::NewLine(NewLineChar::Lf)) => {
+                line.push(ch);
+                lines.push(Vec::new());
+            }
+            (Some(Character::NewLine(NewLineChar::Cr)), _) => {
+                lines.push(vec![ch]);
+            }
+            (_, Character::NewLine(NewLineChar::Lf)) => {
+                line.push(ch);
+                lines.push(Vec::new());
             }
-            Some(Charset::Utf16BigEndian) => CharacterReader::Utf16Be(Utf16BeReader(reader)),
-            Some(Charset::Utf16LittleEndian) => CharacterReader::Utf16Le(Utf16LeReader(reader)),
-            None => CharacterReader::UncheckedEncoding(UncheckedEncodingReader(reader)),
+            _ => line.push(ch),
         }
     }
+    Ok(lines)
 }
@@ -0,0 +1,60 @@
+use std::{fmt::Display, path::Path};
+
+use super::{display_path, escape_xml, Reporter, Summary};
+use crate::{Config, Diagnosis};
+
+/// Write a checkstyle XML report after all files are checked.
+pub struct CheckstyleReporter<W: std::io::Write> {
+    out: W,
+    files: String,
+}
+
+impl<W: std::io::Write> CheckstyleReporter<W> {
+    pub fn new(out: W) -> Self {
+        CheckstyleReporter {
+            out,
+            files: String::new(),
+        }
+    }
+}
+
+impl<W: std::io::Write> Reporter for CheckstyleReporter<W> {
+    fn report(
+        &mut self,
+        file: &Path,
+        _config: &Config,
+        diagnoses: &[Diagnosis],
+    ) -> std::io::Result<()> {
+        self.files.push_str(&format!(
+            "  <file name=\"{}\">\n",
+            escape_xml(&display_path(file))
+        ));
+        for diagnosis in diagnoses {
+            self.files.push_str(&format!(
+                "    <error line=\"{}\" column=\"{}\" severity=\"error\" message=\"{}\" source=\"editorconfig-lint.{}\"/>\n",
+                diagnosis.line,
+                diagnosis.range.0,
+                escape_xml(&diagnosis.reason.to_string()),
+                diagnosis.reason.code(),
+            ));
+        }
+        self.files.push_str("  </file>\n");
+        Ok(())
+    }
+
+    fn report_error(&mut self, file: &Path, error: &dyn Display) -> std::io::Result<()> {
+        self.files.push_str(&format!(
+            "  <file name=\"{}\">\n    <error severity=\"error\" message=\"{}\" source=\"editorconfig-lint\"/>\n  </file>\n",
+            escape_xml(&display_path(file)),
+            escape_xml(&error.to_string()),
+        ));
+        Ok(())
+    }
+
+    fn finish(&mut self, _summary: &Summary) -> std::io::Result<()> {
+        writeln!(self.out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(self.out, r#"<checkstyle version="4.3">"#)?;
+        self.out.write_all(self.files.as_bytes())?;
+        writeln!(self.out, "</checkstyle>")
+    }
+}
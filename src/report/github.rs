@@ -0,0 +1,63 @@
+use std::{fmt::Display, path::Path};
+
+use super::{display_path, Reporter, Summary};
+use crate::{Config, Diagnosis};
+
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Write GitHub Actions workflow commands, which are shown as annotations.
+pub struct GithubReporter<W: std::io::Write>(pub W);
+
+impl<W: std::io::Write> Reporter for GithubReporter<W> {
+    fn report(
+        &mut self,
+        file: &Path,
+        _config: &Config,
+        diagnoses: &[Diagnosis],
+    ) -> std::io::Result<()> {
+        if diagnoses.is_empty() {
+            return Ok(());
+        }
+
+        let file = display_path(file);
+        writeln!(self.0, "::group::{}", escape_data(&file))?;
+        let file = escape_property(&file);
+        for diagnosis in diagnoses {
+            writeln!(
+                self.0,
+                "::error file={file},line={},col={},endColumn={},title={}::{}",
+                diagnosis.line,
+                diagnosis.range.0,
+                diagnosis.range.1,
+                escape_property(diagnosis.reason.code()),
+                escape_data(&diagnosis.reason.to_string()),
+            )?;
+        }
+        writeln!(self.0, "::endgroup::")
+    }
+
+    fn report_error(&mut self, file: &Path, error: &dyn Display) -> std::io::Result<()> {
+        writeln!(
+            self.0,
+            "::error file={}::{}",
+            escape_property(&display_path(file)),
+            escape_data(&error.to_string()),
+        )
+    }
+
+    fn finish(&mut self, summary: &Summary) -> std::io::Result<()> {
+        writeln!(
+            self.0,
+            "::notice title=editorconfig-lint::{} problem(s) in {} file(s), {} file(s) checked",
+            summary.problems, summary.failed_files, summary.checked_files
+        )
+    }
+}
@@ -0,0 +1,234 @@
+use std::{fmt::Display, path::Path};
+
+use unicode_width::UnicodeWidthStr;
+
+use super::{display_path, Reporter, Summary};
+use crate::{
+    reader::{decode, read_lines, Character, IndentChar, NewLineChar},
+    Charset, Config, Diagnosis,
+};
+
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const BLUE: &str = "\x1b[1;34m";
+const CYAN: &str = "\x1b[1;36m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+/// Visible part of the source which covers columns `start..end`
+struct Cell {
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+/// Convert a line into cells. Whitespaces and newlines are visualized only if `visible` returns true.
+fn to_cells(
+    line: &[Character],
+    charset: Option<Charset>,
+    visible: impl Fn(usize) -> bool,
+) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    let mut col = 1;
+    let mut i = 0;
+    while i < line.len() {
+        let mut len = 1;
+        let text = match line[i] {
+            Character::Indent(IndentChar::Space) if visible(col) => "·".to_string(),
+            Character::Indent(IndentChar::Space) => " ".to_string(),
+            Character::Indent(IndentChar::Tab) => "→".to_string(),
+            Character::NewLine(NewLineChar::Cr) if visible(col) => "␍".to_string(),
+            Character::NewLine(NewLineChar::Lf) if visible(col) => "␊".to_string(),
+            Character::NewLine(_) => String::new(),
+            Character::Valid(bytes) if charset.is_none() && bytes.buffer[0] >= 0x80 => {
+                // columns of unchecked encoding are bytes. group bytes of an UTF-8 sequence
+                let mut buf = bytes.as_slice().to_vec();
+                while let Some(Character::Valid(next)) = line.get(i + len) {
+                    if buf.len() >= 4 || next.buffer[0] & 0xC0 != 0x80 {
+                        break;
+                    }
+                    buf.extend_from_slice(next.as_slice());
+                    len += 1;
+                }
+                String::from_utf8_lossy(&buf).to_string()
+            }
+            Character::Valid(bytes) => decode(bytes.as_slice(), charset).to_string(),
+            Character::Invalid(_)
+            | Character::Bom
+            | Character::ReversedBom
+            | Character::Control(_) => char::REPLACEMENT_CHARACTER.to_string(),
+        };
+        cells.push(Cell {
+            text,
+            start: col,
+            end: col + len,
+        });
+        col += len;
+        i += len;
+    }
+    cells
+}
+
+/// Human readable output with source snippets.
+pub struct HumanReporter<W: std::io::Write> {
+    out: W,
+    color: bool,
+}
+
+impl<W: std::io::Write> HumanReporter<W> {
+    pub fn new(out: W, color: bool) -> Self {
+        HumanReporter { out, color }
+    }
+
+    fn paint(&self, color: &'static str, text: impl Display) -> String {
+        if self.color {
+            format!("{color}{text}{RESET}")
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn write_diagnoses(
+        &mut self,
+        file: &Path,
+        lines: &[Vec<Character>],
+        config: &Config,
+        diagnoses: &[Diagnosis],
+    ) -> std::io::Result<()> {
+        let file = display_path(file);
+        for diagnosis in diagnoses {
+            let rule = diagnosis.reason.rule();
+            writeln!(
+                self.out,
+                "{}{}",
+                self.paint(RED, format!("error[{}]", rule.code)),
+                self.paint(BOLD, format!(": {}", diagnosis.reason))
+            )?;
+            writeln!(
+                self.out,
+                "{}{} {file}:{}:{}",
+                " ".repeat(diagnosis.line.to_string().len()),
+                self.paint(BLUE, "-->"),
+                diagnosis.line,
+                diagnosis.range.0
+            )?;
+            if let Some(line) = lines.get(diagnosis.line - 1) {
+                self.write_snippet(diagnosis.line, line, config.charset, diagnosis.range)?;
+            }
+            if let Some(value) = config.property(rule.property) {
+                writeln!(
+                    self.out,
+                    "{} {} .editorconfig expects `{} = {value}`",
+                    " ".repeat(diagnosis.line.to_string().len()),
+                    self.paint(CYAN, "= note:"),
+                    rule.property
+                )?;
+            }
+            writeln!(self.out)?;
+        }
+        Ok(())
+    }
+
+    fn write_snippet(
+        &mut self,
+        line_no: usize,
+        line: &[Character],
+        charset: Option<Charset>,
+        range: (usize, usize),
+    ) -> std::io::Result<()> {
+        let cells = to_cells(line, charset, |col| range.0 <= col && col < range.1);
+        let mut source = String::new();
+        let mut marker = String::new();
+        let mut marked = false;
+        for cell in &cells {
+            let width = cell.text.width();
+            source.push_str(&cell.text);
+            if range.0 == range.1 {
+                // insertion point. mark only the first cell after it
+                if !marked {
+                    if range.0 <= cell.start {
+                        marker.push('^');
+                        marked = true;
+                    } else {
+                        marker.push_str(&" ".repeat(width));
+                    }
+                }
+            } else if range.0 < cell.end && cell.start < range.1 {
+                marker.push_str(&"^".repeat(width.max(1)));
+                marked = true;
+            } else {
+                marker.push_str(&" ".repeat(width));
+            }
+        }
+        if !marked {
+            marker.push('^');
+        }
+
+        let gutter = " ".repeat(line_no.to_string().len());
+        let bar = self.paint(BLUE, "|");
+        writeln!(self.out, "{gutter} {bar}")?;
+        writeln!(self.out, "{} {bar} {source}", self.paint(BLUE, line_no))?;
+        writeln!(
+            self.out,
+            "{gutter} {bar} {}",
+            self.paint(RED, marker.trim_end())
+        )
+    }
+}
+
+impl<W: std::io::Write> Reporter for HumanReporter<W> {
+    fn report(
+        &mut self,
+        file: &Path,
+        config: &Config,
+        diagnoses: &[Diagnosis],
+    ) -> std::io::Result<()> {
+        if diagnoses.is_empty() {
+            return Ok(());
+        }
+
+        // snippets are optional. ignore errors on reading source
+        let lines = std::fs::File::open(file)
+            .and_then(|f| read_lines(std::io::BufReader::new(f), config.charset))
+            .unwrap_or_default();
+        self.write_diagnoses(file, &lines, config, diagnoses)
+    }
+
+    fn report_source(
+        &mut self,
+        file: &Path,
+        source: &[u8],
+        config: &Config,
+        diagnoses: &[Diagnosis],
+    ) -> std::io::Result<()> {
+        if diagnoses.is_empty() {
+            return Ok(());
+        }
+
+        let lines = read_lines(source, config.charset)?;
+        self.write_diagnoses(file, &lines, config, diagnoses)
+    }
+
+    fn report_error(&mut self, file: &Path, error: &dyn Display) -> std::io::Result<()> {
+        writeln!(
+            self.out,
+            "{}{}",
+            self.paint(RED, "error"),
+            self.paint(BOLD, format!(": {}: {error}", display_path(file)))
+        )?;
+        writeln!(self.out)
+    }
+
+    fn finish(&mut self, summary: &Summary) -> std::io::Result<()> {
+        let message = format!(
+            "{} problem(s) in {} file(s), {} file(s) checked",
+            summary.problems, summary.failed_files, summary.checked_files
+        );
+        let color = if summary.failed_files == 0 {
+            CYAN
+        } else {
+            YELLOW
+        };
+        writeln!(self.out, "{}", self.paint(color, message))
+    }
+}
@@ -0,0 +1,125 @@
+use std::{fmt::Display, path::Path};
+
+use super::{display_path, Reporter, Summary};
+use crate::{Config, Diagnosis, Reason};
+
+#[derive(serde::Serialize)]
+struct JsonDiagnosis {
+    file: String,
+    line: usize,
+    column: usize,
+    end_column: usize,
+    #[serde(flatten)]
+    reason: Reason,
+    message: String,
+}
+
+impl JsonDiagnosis {
+    fn new(file: &Path, diagnosis: &Diagnosis) -> Self {
+        JsonDiagnosis {
+            file: display_path(file),
+            line: diagnosis.line,
+            column: diagnosis.range.0,
+            end_column: diagnosis.range.1,
+            reason: diagnosis.reason.clone(),
+            message: diagnosis.reason.to_string(),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct JsonError {
+    file: String,
+    error: String,
+}
+
+impl JsonError {
+    fn new(file: &Path, error: &dyn Display) -> Self {
+        JsonError {
+            file: display_path(file),
+            error: error.to_string(),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+struct JsonDocument<'a> {
+    diagnostics: &'a [JsonDiagnosis],
+    errors: &'a [JsonError],
+    summary: &'a Summary,
+}
+
+/// Write a single JSON document after all files are checked.
+pub struct JsonReporter<W: std::io::Write> {
+    out: W,
+    diagnoses: Vec<JsonDiagnosis>,
+    errors: Vec<JsonError>,
+}
+
+impl<W: std::io::Write> JsonReporter<W> {
+    pub fn new(out: W) -> Self {
+        JsonReporter {
+            out,
+            diagnoses: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+}
+
+impl<W: std::io::Write> Reporter for JsonReporter<W> {
+    fn report(
+        &mut self,
+        file: &Path,
+        _config: &Config,
+        diagnoses: &[Diagnosis],
+    ) -> std::io::Result<()> {
+        for diagnosis in diagnoses {
+            self.diagnoses.push(JsonDiagnosis::new(file, diagnosis));
+        }
+        Ok(())
+    }
+
+    fn report_error(&mut self, file: &Path, error: &dyn Display) -> std::io::Result<()> {
+        self.errors.push(JsonError::new(file, error));
+        Ok(())
+    }
+
+    fn finish(&mut self, summary: &Summary) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(
+            &mut self.out,
+            &JsonDocument {
+                diagnostics: &self.diagnoses,
+                errors: &self.errors,
+                summary,
+            },
+        )?;
+        writeln!(self.out)
+    }
+}
+
+/// Write a JSON object per line as soon as each file is checked.
+pub struct JsonLinesReporter<W: std::io::Write>(pub W);
+
+impl<W: std::io::Write> Reporter for JsonLinesReporter<W> {
+    fn report(
+        &mut self,
+        file: &Path,
+        _config: &Config,
+        diagnoses: &[Diagnosis],
+    ) -> std::io::Result<()> {
+        for diagnosis in diagnoses {
+            serde_json::to_writer(&mut self.0, &JsonDiagnosis::new(file, diagnosis))?;
+            writeln!(self.0)?;
+        }
+        self.0.flush()
+    }
+
+    fn report_error(&mut self, file: &Path, error: &dyn Display) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.0, &JsonError::new(file, error))?;
+        writeln!(self.0)
+    }
+
+    fn finish(&mut self, _summary: &Summary) -> std::io::Result<()> {
+        self.0.flush()
+    }
+}
@@ -0,0 +1,90 @@
+use std::{fmt::Display, path::Path};
+
+use super::{display_path, escape_xml, Reporter, Summary};
+use crate::{Config, Diagnosis};
+
+/// Write a JUnit XML report after all files are checked.
+/// Each file is a test case and each diagnosis is a failure of it.
+pub struct JunitReporter<W: std::io::Write> {
+    out: W,
+    test_cases: String,
+    tests: usize,
+    failures: usize,
+    errors: usize,
+}
+
+impl<W: std::io::Write> JunitReporter<W> {
+    pub fn new(out: W) -> Self {
+        JunitReporter {
+            out,
+            test_cases: String::new(),
+            tests: 0,
+            failures: 0,
+            errors: 0,
+        }
+    }
+}
+
+impl<W: std::io::Write> Reporter for JunitReporter<W> {
+    fn report(
+        &mut self,
+        file: &Path,
+        _config: &Config,
+        diagnoses: &[Diagnosis],
+    ) -> std::io::Result<()> {
+        let file = escape_xml(&display_path(file));
+        self.tests += 1;
+        if diagnoses.is_empty() {
+            self.test_cases.push_str(&format!(
+                "    <testcase name=\"{file}\" classname=\"editorconfig-lint\"/>\n"
+            ));
+            return Ok(());
+        }
+
+        self.failures += 1;
+        self.test_cases.push_str(&format!(
+            "    <testcase name=\"{file}\" classname=\"editorconfig-lint\">\n"
+        ));
+        for diagnosis in diagnoses {
+            let message = escape_xml(&diagnosis.reason.to_string());
+            self.test_cases.push_str(&format!(
+                "      <failure type=\"{}\" message=\"{message}\">{file}:{}:{}: {message}</failure>\n",
+                diagnosis.reason.code(),
+                diagnosis.line,
+                diagnosis.range.0,
+            ));
+        }
+        self.test_cases.push_str("    </testcase>\n");
+        Ok(())
+    }
+
+    fn report_error(&mut self, file: &Path, error: &dyn Display) -> std::io::Result<()> {
+        self.tests += 1;
+        self.errors += 1;
+        self.test_cases.push_str(&format!(
+            "    <testcase name=\"{}\" classname=\"editorconfig-lint\">\n      <error message=\"{}\"/>\n    </testcase>\n",
+            escape_xml(&display_path(file)),
+            escape_xml(&error.to_string()),
+        ));
+        Ok(())
+    }
+
+    fn finish(&mut self, _summary: &Summary) -> std::io::Result<()> {
+        let counts = format!(
+            "tests=\"{}\" failures=\"{}\" errors=\"{}\"",
+            self.tests, self.failures, self.errors
+        );
+        writeln!(self.out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            self.out,
+            r#"<testsuites name="editorconfig-lint" {counts}>"#
+        )?;
+        writeln!(
+            self.out,
+            r#"  <testsuite name="editorconfig-lint" {counts}>"#
+        )?;
+        self.out.write_all(self.test_cases.as_bytes())?;
+        writeln!(self.out, "  </testsuite>")?;
+        writeln!(self.out, "</testsuites>")
+    }
+}
@@ -0,0 +1,100 @@
+use std::{fmt::Display, path::Path};
+
+use crate::{Config, Diagnosis};
+
+mod sarif;
+pub use sarif::*;
+
+mod checkstyle;
+pub use checkstyle::*;
+
+mod github;
+pub use github::*;
+
+mod human;
+pub use human::*;
+
+mod json;
+pub use json::*;
+
+mod junit;
+pub use junit::*;
+
+/// Path for display without leading `./`
+pub(crate) fn display_path(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    path.strip_prefix("./").unwrap_or(&path).to_string()
+}
+
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for ch in s.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[derive(serde::Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Summary {
+    pub checked_files: usize,
+    pub failed_files: usize,
+    pub problems: usize,
+}
+
+/// Output of check results. Every checked file is passed to `report` in order
+/// with its effective config, even if it has no diagnosis.
+pub trait Reporter {
+    fn report(
+        &mut self,
+        file: &Path,
+        config: &Config,
+        diagnoses: &[Diagnosis],
+    ) -> std::io::Result<()>;
+    /// Same as [`Reporter::report`] for `source` which is not read from `file`
+    fn report_source(
+        &mut self,
+        file: &Path,
+        _source: &[u8],
+        config: &Config,
+        diagnoses: &[Diagnosis],
+    ) -> std::io::Result<()> {
+        self.report(file, config, diagnoses)
+    }
+    fn report_error(&mut self, file: &Path, error: &dyn Display) -> std::io::Result<()>;
+    fn finish(&mut self, summary: &Summary) -> std::io::Result<()>;
+}
+
+pub struct TextReporter<W: std::io::Write>(pub W);
+
+impl<W: std::io::Write> Reporter for TextReporter<W> {
+    fn report(
+        &mut self,
+        file: &Path,
+        _config: &Config,
+        diagnoses: &[Diagnosis],
+    ) -> std::io::Result<()> {
+        for diagnosis in diagnoses {
+            diagnosis.fmt(&mut self.0, &file.display())?;
+        }
+        Ok(())
+    }
+
+    fn report_error(&mut self, file: &Path, error: &dyn Display) -> std::io::Result<()> {
+        writeln!(self.0, "error: {}: {error}", file.display())
+    }
+
+    fn finish(&mut self, summary: &Summary) -> std::io::Result<()> {
+        writeln!(
+            self.0,
+            "{} problem(s) in {} file(s), {} file(s) checked",
+            summary.problems, summary.failed_files, summary.checked_files
+        )
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    fmt::Display,
+    path::{Component, Path, PathBuf},
+};
+
+use super::{Reporter, Summary};
+use crate::{config::normalize, Config, Diagnosis, RULES};
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Base of relative `artifactLocation.uri`, which is the current directory
+const SRCROOT: &str = "%SRCROOT%";
+
+/// Percent-encode `s` as a segment of URI path. `:` is kept if `keep_colon`
+fn push_encoded(uri: &mut String, s: &str, keep_colon: bool) {
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                uri.push(byte as char)
+            }
+            b':' if keep_colon => uri.push(':'),
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+}
+
+/// URI reference of `relative` path which does not contain `..`
+fn to_relative_uri(relative: &Path) -> String {
+    let mut uri = String::new();
+    for component in relative.components() {
+        if let Component::Normal(name) = component {
+            if !uri.is_empty() {
+                uri.push('/');
+            }
+            push_encoded(&mut uri, &name.to_string_lossy(), false);
+        }
+    }
+    uri
+}
+
+/// `file` URI of normalized `absolute` path
+fn to_file_uri(absolute: &Path) -> String {
+    let mut uri = String::from("file://");
+    for component in absolute.components() {
+        match component {
+            // drive letter such as `C:`
+            Component::Prefix(prefix) => {
+                uri.push('/');
+                push_encoded(&mut uri, &prefix.as_os_str().to_string_lossy(), true);
+            }
+            Component::Normal(name) => {
+                uri.push('/');
+                push_encoded(&mut uri, &name.to_string_lossy(), false);
+            }
+            Component::RootDir | Component::CurDir | Component::ParentDir => {}
+        }
+    }
+    uri
+}
+
+/// Write a SARIF 2.1.0 log after all files are checked.
+pub struct SarifReporter<W: std::io::Write> {
+    out: W,
+    /// current directory which is `%SRCROOT%`
+    root: Option<PathBuf>,
+    results: Vec<serde_json::Value>,
+    notifications: Vec<serde_json::Value>,
+}
+
+impl<W: std::io::Write> SarifReporter<W> {
+    pub fn new(out: W) -> Self {
+        SarifReporter {
+            out,
+            root: std::env::current_dir().ok(),
+            results: Vec::new(),
+            notifications: Vec::new(),
+        }
+    }
+
+    /// `artifactLocation` of `file`. It is relative to `%SRCROOT%` if `file` is under it
+    fn artifact_location(&self, file: &Path) -> serde_json::Value {
+        let absolute = match normalize(file) {
+            Ok(absolute) => absolute,
+            Err(_) => return serde_json::json!({ "uri": to_relative_uri(file) }),
+        };
+        match self
+            .root
+            .as_ref()
+            .and_then(|root| absolute.strip_prefix(root).ok())
+        {
+            Some(relative) => serde_json::json!({
+                "uri": to_relative_uri(relative),
+                "uriBaseId": SRCROOT,
+            }),
+            None => serde_json::json!({ "uri": to_file_uri(&absolute) }),
+        }
+    }
+}
+
+impl<W: std::io::Write> Reporter for SarifReporter<W> {
+    fn report(
+        &mut self,
+        file: &Path,
+        config: &Config,
+        diagnoses: &[Diagnosis],
+    ) -> std::io::Result<()> {
+        let location = self.artifact_location(file);
+        for diagnosis in diagnoses {
+            let rule = diagnosis.reason.rule();
+            let rule_index = RULES.iter().position(|r| r == rule);
+            self.results.push(serde_json::json!({
+                "ruleId": rule.code,
+                "ruleIndex": rule_index,
+                "level": "error",
+                "message": { "text": diagnosis.reason.to_string() },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": location,
+                        "region": {
+                            "startLine": diagnosis.line,
+                            "startColumn": diagnosis.range.0,
+                            "endColumn": diagnosis.range.1,
+                        },
+                    },
+                }],
+                "properties": {
+                    "editorconfig": {
+                        "property": rule.property,
+                        "value": config.property(rule.property),
+                    },
+                },
+            }));
+        }
+        Ok(())
+    }
+
+    fn report_error(&mut self, file: &Path, error: &dyn Display) -> std::io::Result<()> {
+        self.notifications.push(serde_json::json!({
+            "level": "error",
+            "message": { "text": error.to_string() },
+            "locations": [{
+                "physicalLocation": {
+                    "artifactLocation": self.artifact_location(file),
+                },
+            }],
+        }));
+        Ok(())
+    }
+
+    fn finish(&mut self, _summary: &Summary) -> std::io::Result<()> {
+        let rules = RULES
+            .iter()
+            .map(|rule| {
+                serde_json::json!({
+                    "id": rule.code,
+                    "shortDescription": { "text": rule.description },
+                    "defaultConfiguration": { "level": "error" },
+                    "properties": { "editorconfig-property": rule.property },
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut run = serde_json::json!({
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "invocations": [{
+                "executionSuccessful": self.notifications.is_empty(),
+                "toolExecutionNotifications": self.notifications,
+            }],
+            "results": self.results,
+        });
+        if let Some(root) = &self.root {
+            // a base URI must end with `/`
+            run["originalUriBaseIds"] = serde_json::json!({
+                SRCROOT: { "uri": to_file_uri(root) + "/" },
+            });
+        }
+        let log = serde_json::json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": [run],
+        });
+        serde_json::to_writer_pretty(&mut self.out, &log)?;
+        writeln!(self.out)
+    }
+}
@@ -0,0 +1,94 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use crate::{
+    config::{resolve_config, CompiledConfig},
+    Config, Error, PathResolution, RawConfig,
+};
+
+/// State of a config file when it was parsed
+#[derive(PartialEq, Eq)]
+struct Stamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl Stamp {
+    /// Returns `None` if there is no file at `path`
+    fn of(path: &Path) -> Option<Stamp> {
+        let metadata = std::fs::metadata(path).ok()?;
+        if !metadata.is_file() {
+            return None;
+        }
+        Some(Stamp {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+struct Entry {
+    stamp: Option<Stamp>,
+    config: Option<Arc<CompiledConfig>>,
+}
+
+/// Resolve configs like [`Config::get_config_for`], but each `.editorconfig` is parsed
+/// and its patterns are compiled only once. A file is parsed again when it is modified.
+/// It can be shared between threads.
+#[derive(Default)]
+pub struct ConfigResolver {
+    /// keyed by the path of config file
+    files: Mutex<HashMap<PathBuf, Entry>>,
+    resolution: PathResolution,
+}
+
+impl ConfigResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolver which resolves paths like [`Config::get_config_for_with`]
+    pub fn with_path_resolution(resolution: PathResolution) -> Self {
+        Self {
+            resolution,
+            ..Self::default()
+        }
+    }
+
+    fn load(&self, config_path: &Path) -> Result<Option<Arc<CompiledConfig>>, Error> {
+        let stamp = Stamp::of(config_path);
+        if let Some(entry) = self.files.lock().unwrap().get(config_path) {
+            if entry.stamp == stamp {
+                return Ok(entry.config.clone());
+            }
+        }
+
+        // parse without lock. other threads may parse the same file at the same time
+        let config = match stamp {
+            Some(_) => RawConfig::open(config_path)?.map(|config| Arc::new(config.compile())),
+            None => None,
+        };
+        self.files.lock().unwrap().insert(
+            config_path.to_path_buf(),
+            Entry {
+                stamp,
+                config: config.clone(),
+            },
+        );
+        Ok(config)
+    }
+
+    /// Resolve the effective config for `path`. Same as [`Config::get_config_for`].
+    pub fn get_config_for(&self, path: &Path) -> Result<Config, Error> {
+        resolve_config(path, self.resolution, |config_path| self.load(config_path))
+    }
+
+    /// Drop all cached config files
+    pub fn clear(&self) {
+        self.files.lock().unwrap().clear();
+    }
+}
@@ -0,0 +1,128 @@
+use std::path::Path;
+
+use editorconfig_lint::{check, Baseline, Config};
+
+mod common;
+use common::{run, temp_dir, write_files};
+
+fn diagnose(source: &str) -> Vec<editorconfig_lint::Diagnosis> {
+    let config = Config {
+        trim_trailing_whitespace: Some(true),
+        ..Config::default()
+    };
+    check(source.as_bytes(), config).unwrap()
+}
+
+#[test]
+fn suppress_moved_lines() {
+    let dir = temp_dir("baseline-moved");
+    let path = dir.join("baseline.json");
+    let file = dir.join("missing/a.txt");
+    let file = file.as_path();
+    let source = "a \nb \nb \n";
+    let mut baseline = Baseline::new(&path).unwrap();
+    baseline
+        .add(file, source.as_bytes(), None, &diagnose(source))
+        .unwrap();
+    assert_eq!(baseline.len(), 3);
+
+    baseline.save(&path).unwrap();
+    let mut baseline = Baseline::open(&path).unwrap();
+    std::fs::remove_dir_all(dir).unwrap();
+
+    // lines are moved, one of the same lines is fixed and a new one is added
+    let source = "new \nb \nb\na \n";
+    let mut diagnoses = diagnose(source);
+    baseline
+        .suppress(file, source.as_bytes(), None, &mut diagnoses)
+        .unwrap();
+    assert_eq!(diagnoses.iter().map(|d| d.line).collect::<Vec<_>>(), [1]);
+    let stale = baseline.stale();
+    assert_eq!(stale.len(), 1);
+    assert_eq!(stale[0].0, "missing/a.txt");
+    assert_eq!(stale[0].1.reason, "trailing-white-spaces");
+}
+
+#[test]
+fn stale_removed_files() {
+    let dir = temp_dir("baseline-removed");
+    let source = "a \n";
+    let mut baseline = Baseline::new(&dir.join("baseline.json")).unwrap();
+    for file in ["missing/a.txt", "missing/b.txt"] {
+        baseline
+            .add(&dir.join(file), source.as_bytes(), None, &diagnose(source))
+            .unwrap();
+    }
+
+    let mut diagnoses = diagnose(source);
+    baseline
+        .suppress(
+            &dir.join("./missing/a.txt"),
+            source.as_bytes(),
+            None,
+            &mut diagnoses,
+        )
+        .unwrap();
+    assert!(diagnoses.is_empty());
+    let stale = baseline.stale();
+    assert_eq!(stale.len(), 1);
+    assert_eq!(stale[0].0, "missing/b.txt");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn other_working_directory() {
+    let dir = temp_dir("baseline-cwd");
+    write_files(
+        &dir,
+        &[
+            (
+                ".editorconfig",
+                "root = true\n[*.txt]\ntrim_trailing_whitespace = true\n",
+            ),
+            ("sub/a.txt", "a \n"),
+            ("b.txt", "b \n"),
+        ],
+    );
+    let check =
+        |cwd: &Path, args: &[&str]| run(cwd, &[&["check", "--format", "text"], args].concat());
+
+    assert!(check(&dir, &[".", "--write-baseline", "baseline.json"]).success);
+    let baseline = std::fs::read_to_string(dir.join("baseline.json")).unwrap();
+    assert!(baseline.contains("\"sub/a.txt\""), "{baseline}");
+
+    let baseline = dir.join("baseline.json");
+    let baseline = baseline.to_str().unwrap();
+    let sub = dir.join("sub");
+    let runs: [(&Path, &[&str]); 3] = [
+        (&sub, &[".", "--baseline", "../baseline.json"]),
+        (&sub, &["a.txt", "../b.txt", "--baseline", baseline]),
+        (&dir, &[sub.to_str().unwrap(), "--baseline", baseline]),
+    ];
+    for (cwd, args) in runs {
+        let output = check(cwd, args);
+        assert!(
+            output.success,
+            "{args:?}: {}{}",
+            output.stdout, output.stderr
+        );
+        assert!(
+            output.stdout.starts_with("0 problem(s)"),
+            "{args:?}: {}",
+            output.stdout
+        );
+        assert!(
+            !output.stderr.contains("stale"),
+            "{args:?}: {}",
+            output.stderr
+        );
+    }
+
+    std::fs::write(dir.join("sub/a.txt"), "a\n").unwrap();
+    let stderr = check(&sub, &[".", "--baseline", "../baseline.json"]).stderr;
+    assert!(
+        stderr.contains("stale baseline entry: sub/a.txt: trailing-white-spaces"),
+        "{stderr}"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
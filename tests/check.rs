@@ -0,0 +1,38 @@
+mod common;
+use common::{run, temp_dir, write_files};
+
+#[test]
+fn exit_status_and_summary() {
+    let dir = temp_dir("check");
+    write_files(
+        &dir,
+        &[
+            (
+                ".editorconfig",
+                "root = true\n[*.txt]\ntrim_trailing_whitespace = true\n",
+            ),
+            ("a.txt", "a\n"),
+            ("sub/deep/b.txt", "b \n"),
+            ("sub/c.txt", "c\n"),
+            // no section matches
+            ("sub/d.md", "d \n"),
+        ],
+    );
+    let check = |args: &[&str]| run(&dir, &[&["check", "--format", "text"], args].concat());
+
+    let output = check(&["a.txt", "sub"]);
+    assert!(!output.success);
+    assert_eq!(
+        output.stdout,
+        "error: trailing whitespace at sub/deep/b.txt:1:2,3\n\
+         1 problem(s) in 1 file(s), 3 file(s) checked\n"
+    );
+
+    let output = check(&["a.txt", "sub/c.txt", "sub/d.md"]);
+    assert!(output.success);
+    assert_eq!(
+        output.stdout,
+        "0 problem(s) in 0 file(s), 2 file(s) checked\n"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
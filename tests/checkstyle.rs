@@ -0,0 +1,41 @@
+use std::path::Path;
+
+use editorconfig_lint::{CheckstyleReporter, Config, Diagnosis, Reason, Reporter, Summary};
+
+#[test]
+fn escaped_report() {
+    let mut out = Vec::new();
+    let mut reporter = CheckstyleReporter::new(&mut out);
+    let config = Config::default();
+    let diagnosis = Diagnosis {
+        line: 2,
+        range: (3, 4),
+        reason: Reason::TrailingWhiteSpaces,
+    };
+    reporter
+        .report(Path::new("./a<&>.txt"), &config, &[diagnosis])
+        .unwrap();
+    reporter
+        .report(Path::new("./clean.txt"), &config, &[])
+        .unwrap();
+    reporter
+        .report_error(Path::new("\"b'.txt"), &"bad <value> & \"more\"")
+        .unwrap();
+    reporter.finish(&Summary::default()).unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<checkstyle version="4.3">
+  <file name="a&lt;&amp;&gt;.txt">
+    <error line="2" column="3" severity="error" message="trailing whitespace" source="editorconfig-lint.trailing-white-spaces"/>
+  </file>
+  <file name="clean.txt">
+  </file>
+  <file name="&quot;b&apos;.txt">
+    <error severity="error" message="bad &lt;value&gt; &amp; &quot;more&quot;" source="editorconfig-lint"/>
+  </file>
+</checkstyle>
+"#
+    );
+}
@@ -0,0 +1,57 @@
+use std::io::BufReader;
+
+use editorconfig_lint::{check, fix, Charset, Config, IndentSize, IndentStyle, LineEnding};
+
+const INPUT: &str = "\u{FEFF}fn main() {  \r\n\tlet s = \"가나다 😀\";\n    s\u{a0}\n}";
+
+fn encode(charset: Charset) -> Vec<u8> {
+    match charset {
+        Charset::Utf16LittleEndian => INPUT.encode_utf16().flat_map(u16::to_le_bytes).collect(),
+        Charset::Utf16BigEndian => INPUT.encode_utf16().flat_map(u16::to_be_bytes).collect(),
+        _ => {
+            let mut bytes = INPUT.as_bytes().to_vec();
+            // an invalid sequence followed by ASCII
+            bytes.extend_from_slice(b"\xE3\x81x\xF0\x9F");
+            bytes
+        }
+    }
+}
+
+/// Results must not depend on how the input is split into `fill_buf` slices
+#[test]
+fn split_characters() {
+    for charset in [
+        Charset::Utf8,
+        Charset::Utf8WithBom,
+        Charset::Latin1,
+        Charset::Utf16LittleEndian,
+        Charset::Utf16BigEndian,
+    ] {
+        let config = Config {
+            charset: Some(charset),
+            indent_style: Some(IndentStyle::Space),
+            indent_size: Some(IndentSize::Columns(4)),
+            end_of_line: Some(LineEnding::Lf),
+            trim_trailing_whitespace: Some(true),
+            insert_final_newline: Some(true),
+            ..Default::default()
+        };
+        let input = encode(charset);
+        let expected_diagnoses = check(&input[..], config.clone()).unwrap();
+        let expected_fixed = fix(&input[..], Vec::new(), &config).unwrap();
+        for capacity in 1..=8 {
+            let reader = BufReader::with_capacity(capacity, &input[..]);
+            assert_eq!(
+                check(reader, config.clone()).unwrap(),
+                expected_diagnoses,
+                "{charset:?} with capacity {capacity}"
+            );
+            let reader = BufReader::with_capacity(capacity, &input[..]);
+            assert_eq!(
+                fix(reader, Vec::new(), &config).unwrap(),
+                expected_fixed,
+                "{charset:?} with capacity {capacity}"
+            );
+        }
+    }
+}
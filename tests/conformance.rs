@@ -0,0 +1,100 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use editorconfig_lint::{Config, Error};
+
+mod common;
+use common::temp_dir;
+
+const SUITES: [&str; 4] = ["glob", "parser", "properties", "filetree"];
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum ConfigFiles {
+    Single(String),
+    /// directory to config file
+    Tree(BTreeMap<String, String>),
+}
+
+#[derive(serde::Deserialize)]
+struct Case {
+    name: String,
+    config: ConfigFiles,
+    path: String,
+    expect: BTreeMap<String, String>,
+}
+
+fn fixture_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/editorconfig-core-test")
+}
+
+fn run(suite_dir: &Path, work_dir: &Path, case: &Case) -> Result<(), String> {
+    let dir = work_dir.join(&case.name);
+    let configs = match &case.config {
+        ConfigFiles::Single(file) => BTreeMap::from([(String::new(), file.clone())]),
+        ConfigFiles::Tree(files) => files.clone(),
+    };
+    for (config_dir, file) in configs {
+        let config_dir = dir.join(config_dir);
+        std::fs::create_dir_all(&config_dir).unwrap();
+        std::fs::copy(suite_dir.join(file), config_dir.join(".editorconfig")).unwrap();
+    }
+    let path = dir.join(&case.path);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "").unwrap();
+
+    let actual: BTreeMap<String, String> = match Config::get_config_for(&path) {
+        Ok(config) => config.properties(),
+        Err(Error::NotFound) => BTreeMap::new(),
+        Err(e) => return Err(e.to_string()),
+    };
+    if actual == case.expect {
+        Ok(())
+    } else {
+        Err(format!("expected {:?}, got {actual:?}", case.expect))
+    }
+}
+
+/// Run every case and compare failures with `known_failures.txt`
+#[test]
+fn editorconfig_core_test() {
+    let fixture_dir = fixture_dir();
+    let known_failures = std::fs::read_to_string(fixture_dir.join("known_failures.txt")).unwrap();
+    let known_failures: BTreeSet<&str> = known_failures
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+
+    let work_dir = temp_dir("conformance");
+    let mut unexpected = Vec::new();
+    for suite in SUITES {
+        let suite_dir = fixture_dir.join(suite);
+        let cases: Vec<Case> =
+            serde_json::from_slice(&std::fs::read(suite_dir.join("cases.json")).unwrap()).unwrap();
+        let mut passed = 0;
+        for case in &cases {
+            let id = format!("{suite}/{}", case.name);
+            let result = run(&suite_dir, &work_dir.join(suite), case);
+            match &result {
+                Ok(()) => {
+                    passed += 1;
+                    println!("{id}: ok");
+                }
+                Err(message) => println!("{id}: FAILED - {message}"),
+            }
+            if result.is_ok() == known_failures.contains(id.as_str()) {
+                unexpected.push(id);
+            }
+        }
+        println!("{suite}: {passed}/{} passed", cases.len());
+    }
+    let _ = std::fs::remove_dir_all(&work_dir);
+
+    assert!(
+        unexpected.is_empty(),
+        "results differ from known_failures.txt: {unexpected:?}"
+    );
+}
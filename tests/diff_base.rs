@@ -0,0 +1,128 @@
+use std::path::Path;
+
+mod common;
+use common::{git, run, temp_dir};
+
+fn check(dir: &Path, args: &[&str]) -> String {
+    run(dir, &[&["check", "--format", "text", "."], args].concat()).stdout
+}
+
+#[test]
+fn only_changed_lines() {
+    let dir = temp_dir("diff");
+    git(&dir, &["init", "-q"]);
+    std::fs::write(
+        dir.join(".editorconfig"),
+        "root = true\n[*.txt]\ntrim_trailing_whitespace = true\ninsert_final_newline = true\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("a.txt"), "a \nb \nc \n").unwrap();
+    std::fs::write(dir.join("b.txt"), "a \n").unwrap();
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "-q", "-m", "initial"]);
+
+    std::fs::write(dir.join("a.txt"), "a \nB \nc \nd").unwrap();
+    std::fs::write(dir.join("c.txt"), "a \n").unwrap();
+
+    assert_eq!(
+        check(&dir, &["--diff-base", "HEAD"]),
+        "error: trailing whitespace at ./a.txt:2:2,3\n\
+         error: missing newline at end of file at ./a.txt:4:2\n\
+         error: trailing whitespace at ./c.txt:1:2,3\n\
+         3 problem(s) in 2 file(s), 2 file(s) checked\n"
+    );
+
+    // only the index is compared. untracked files are not changed
+    git(&dir, &["add", "a.txt"]);
+    std::fs::write(dir.join("a.txt"), "a \nB \nC \nd").unwrap();
+    assert_eq!(
+        check(&dir, &["--staged"]),
+        "error: trailing whitespace at ./a.txt:2:2,3\n\
+         error: missing newline at end of file at ./a.txt:4:2\n\
+         2 problem(s) in 1 file(s), 1 file(s) checked\n"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn special_file_names() {
+    let dir = temp_dir("diff-names");
+    git(&dir, &["init", "-q"]);
+    std::fs::write(
+        dir.join(".editorconfig"),
+        "root = true\n[*.txt]\ntrim_trailing_whitespace = true\n",
+    )
+    .unwrap();
+    let names = [
+        "with space.txt",
+        "with \"quote\".txt",
+        "tab\there.txt",
+        "ünïcode.txt",
+    ];
+    for name in names {
+        std::fs::write(dir.join(name), "a \nb \n").unwrap();
+    }
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "-q", "-m", "initial"]);
+    for name in names {
+        std::fs::write(dir.join(name), "a \nB \n").unwrap();
+    }
+
+    let output = check(&dir, &["--diff-base", "HEAD"]);
+    for name in names {
+        assert!(
+            output.contains(&format!("error: trailing whitespace at ./{name}:2:2,3\n")),
+            "{name:?} in {output}"
+        );
+    }
+    assert!(output.ends_with("4 problem(s) in 4 file(s), 4 file(s) checked\n"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn latin1_changed_line() {
+    let dir = temp_dir("diff-latin1");
+    git(&dir, &["init", "-q"]);
+    std::fs::write(
+        dir.join(".editorconfig"),
+        "root = true\n[*.txt]\ncharset = latin1\ntrim_trailing_whitespace = true\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("a.txt"), b"caf\xe9\na \n").unwrap();
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "-q", "-m", "initial"]);
+    std::fs::write(dir.join("a.txt"), b"caf\xe9 \na \n").unwrap();
+
+    assert_eq!(
+        check(&dir, &["--diff-base", "HEAD"]),
+        "error: trailing whitespace at ./a.txt:1:5,6\n\
+         1 problem(s) in 1 file(s), 1 file(s) checked\n"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn staged_content_is_checked() {
+    let dir = temp_dir("diff-staged");
+    git(&dir, &["init", "-q"]);
+    std::fs::write(
+        dir.join(".editorconfig"),
+        "root = true\n[*.txt]\ntrim_trailing_whitespace = true\n",
+    )
+    .unwrap();
+    git(&dir, &["add", "."]);
+    git(&dir, &["commit", "-q", "-m", "initial"]);
+    std::fs::write(dir.join("a.txt"), "a\nb \n").unwrap();
+    git(&dir, &["add", "a.txt"]);
+    // lines of the working tree no longer match the index
+    std::fs::write(dir.join("a.txt"), "new\na\nb \n").unwrap();
+
+    assert_eq!(
+        check(&dir, &["--staged"]),
+        "error: trailing whitespace at ./a.txt:2:2,3\n\
+         1 problem(s) in 1 file(s), 1 file(s) checked\n"
+    );
+    let output = run(&dir, &["check", "--color", "never", "--staged", "."]).stdout;
+    assert!(output.contains("2 | b·\n"), "{output}");
+    std::fs::remove_dir_all(dir).unwrap();
+}
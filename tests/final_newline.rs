@@ -0,0 +1,55 @@
+use editorconfig_lint::{check, Config, Diagnosis, Reason};
+
+fn diagnose(input: &str, insert_final_newline: bool) -> Vec<Diagnosis> {
+    let config = Config {
+        insert_final_newline: Some(insert_final_newline),
+        ..Config::default()
+    };
+    check(input.as_bytes(), config).unwrap()
+}
+
+fn diagnosis(line: usize, range: (usize, usize), reason: Reason) -> Diagnosis {
+    Diagnosis {
+        line,
+        range,
+        reason,
+    }
+}
+
+#[test]
+fn superfluous_final_newline() {
+    for (input, line, range) in [
+        ("a\n", 1, (2, 3)),
+        ("a\r\n", 1, (2, 4)),
+        ("a\r", 1, (2, 3)),
+        ("a\n\n", 2, (1, 2)),
+        ("a\r\r", 2, (1, 2)),
+        ("a\r\n\r\n", 2, (1, 3)),
+        ("a\n\r\n", 2, (1, 3)),
+        ("\n", 1, (1, 2)),
+    ] {
+        assert_eq!(
+            diagnose(input, false),
+            [diagnosis(line, range, Reason::SuperfluousFinalNewline)],
+            "{input:?}"
+        );
+    }
+}
+
+#[test]
+fn no_final_newline() {
+    for (input, line, col) in [
+        ("a", 1, 2),
+        ("a\nbc", 2, 3),
+        ("a\r\rb", 3, 2),
+        ("a\r\nb", 2, 2),
+    ] {
+        assert_eq!(
+            diagnose(input, true),
+            [diagnosis(line, (col, col), Reason::NoFinalNewline)],
+            "{input:?}"
+        );
+    }
+    assert_eq!(diagnose("", true), []);
+    assert_eq!(diagnose("a\n", true), []);
+}
@@ -0,0 +1,223 @@
+use std::path::PathBuf;
+
+use editorconfig_lint::{
+    check, fix, Charset, Config, Diagnosis, IndentSize, IndentStyle, LineEnding,
+};
+
+mod common;
+use common::{run, temp_dir, write_files};
+
+/// Fixed `input` and diagnoses of it
+fn fix_and_check(input: &str, config: &Config) -> (String, Vec<Diagnosis>) {
+    let fixed = fix(input.as_bytes(), Vec::new(), config).unwrap();
+    let diagnoses = check(&fixed[..], config.clone()).unwrap();
+    (String::from_utf8(fixed).unwrap(), diagnoses)
+}
+
+/// Directory with an `.editorconfig` for fixable reasons
+fn project(name: &str) -> PathBuf {
+    let dir = temp_dir(name);
+    write_files(
+        &dir,
+        &[(
+            ".editorconfig",
+            "root = true\n[*]\nindent_style = space\nindent_size = 4\ntrim_trailing_whitespace = true\n",
+        )],
+    );
+    std::fs::create_dir(dir.join("sub")).unwrap();
+    dir
+}
+
+#[cfg(unix)]
+#[test]
+fn symlink_target_is_fixed() {
+    let dir = project("fix-symlink");
+    std::fs::write(dir.join("sub/a.txt"), "\tx \n").unwrap();
+    std::os::unix::fs::symlink("sub/a.txt", dir.join("link.txt")).unwrap();
+
+    run(&dir, &["fix", "link.txt"]);
+    assert!(std::fs::symlink_metadata(dir.join("link.txt"))
+        .unwrap()
+        .file_type()
+        .is_symlink());
+    assert_eq!(
+        std::fs::read_to_string(dir.join("sub/a.txt")).unwrap(),
+        "    x\n"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn indent_size_without_indent_style() {
+    let config = Config {
+        indent_size: Some(IndentSize::Columns(4)),
+        ..Config::default()
+    };
+    assert_eq!(
+        fix_and_check("  x\n\t y\nz\n", &config),
+        ("    x\n\t    y\nz\n".to_string(), vec![])
+    );
+}
+
+#[test]
+fn indent_is_rounded_up() {
+    let config = Config {
+        indent_style: Some(IndentStyle::Space),
+        indent_size: Some(IndentSize::Columns(4)),
+        ..Config::default()
+    };
+    assert_eq!(
+        fix_and_check(" a\n     b\n", &config),
+        ("    a\n        b\n".to_string(), vec![])
+    );
+
+    let config = Config {
+        indent_style: Some(IndentStyle::Tab),
+        indent_size: Some(IndentSize::Tab),
+        tab_width: Some(4),
+        ..Config::default()
+    };
+    assert_eq!(
+        fix_and_check(" a\n\t  b\n", &config),
+        ("\ta\n\t\tb\n".to_string(), vec![])
+    );
+}
+
+/// Every diagnosis of `input` is fixed into `expected`
+fn assert_fixed(input: &str, config: Config, expected: &str) {
+    assert!(!check(input.as_bytes(), config.clone()).unwrap().is_empty());
+    assert_eq!(
+        fix_and_check(input, &config),
+        (expected.to_string(), vec![]),
+        "{input:?}"
+    );
+}
+
+#[test]
+fn end_of_line() {
+    for (end_of_line, expected) in [
+        (LineEnding::Lf, "a\nb\nc\nd"),
+        (LineEnding::Crlf, "a\r\nb\r\nc\r\nd"),
+        (LineEnding::Cr, "a\rb\rc\rd"),
+    ] {
+        let config = Config {
+            end_of_line: Some(end_of_line),
+            ..Config::default()
+        };
+        assert_fixed("a\r\nb\nc\rd", config, expected);
+    }
+}
+
+#[test]
+fn trailing_whitespace() {
+    let config = Config {
+        trim_trailing_whitespace: Some(true),
+        ..Config::default()
+    };
+    assert_fixed("a \t\n  \nb\t", config, "a\n\nb");
+}
+
+#[test]
+fn final_newline() {
+    let config = Config {
+        insert_final_newline: Some(true),
+        ..Config::default()
+    };
+    assert_fixed("a\nb", config, "a\nb\n");
+
+    let config = Config {
+        insert_final_newline: Some(false),
+        ..Config::default()
+    };
+    assert_fixed("a\nb\n\n", config, "a\nb");
+}
+
+#[test]
+fn indent_style() {
+    let config = Config {
+        indent_style: Some(IndentStyle::Space),
+        indent_size: Some(IndentSize::Columns(2)),
+        tab_width: Some(2),
+        ..Config::default()
+    };
+    assert_fixed("\ta\n\t\tb\n", config, "  a\n    b\n");
+
+    let config = Config {
+        indent_style: Some(IndentStyle::Tab),
+        indent_size: Some(IndentSize::Tab),
+        tab_width: Some(2),
+        ..Config::default()
+    };
+    assert_fixed("  a\n    b\n", config, "\ta\n\t\tb\n");
+}
+
+#[test]
+fn bom() {
+    let config = Config {
+        charset: Some(Charset::Utf8WithBom),
+        ..Config::default()
+    };
+    assert_fixed("a\n", config, "\u{FEFF}a\n");
+
+    let config = Config {
+        charset: Some(Charset::Utf8),
+        ..Config::default()
+    };
+    assert_fixed("\u{FEFF}a\n", config, "a\n");
+}
+
+#[test]
+fn clean_file_is_not_rewritten() {
+    let dir = project("fix-clean");
+    let path = dir.join("a.txt");
+    std::fs::write(&path, "a\n    b\n").unwrap();
+    let modified = std::fs::metadata(&path).unwrap().modified().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(20));
+
+    assert_eq!(run(&dir, &["fix", "."]).stdout, "");
+    assert_eq!(
+        std::fs::metadata(&path).unwrap().modified().unwrap(),
+        modified
+    );
+
+    std::fs::write(&path, "a \n").unwrap();
+    assert_eq!(run(&dir, &["fix", "."]).stdout, "fixed: ./a.txt\n");
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "a\n");
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn binary_file_is_not_fixed() {
+    let dir = temp_dir("fix-binary");
+    write_files(
+        &dir,
+        &[
+            (
+                ".editorconfig",
+                "root = true\n[*]\nend_of_line = lf\n[*.utf16]\ncharset = utf-16le\n",
+            ),
+            ("a.txt", "a\r\n"),
+        ],
+    );
+    let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\r\n";
+    std::fs::write(dir.join("img.png"), png).unwrap();
+    // UTF-16 text contains NUL, but it is not binary
+    std::fs::write(dir.join("a.utf16"), b"\xFF\xFEa\0\r\0\n\0").unwrap();
+
+    assert_eq!(
+        run(&dir, &["check", "--format", "text", "."]).stdout,
+        "error: expected lf line ending, found crlf at ./a.txt:1:2,4\n\
+         error: expected lf line ending, found crlf at ./a.utf16:1:2,4\n\
+         2 problem(s) in 2 file(s), 3 file(s) checked\n"
+    );
+    assert_eq!(
+        run(&dir, &["fix", "."]).stdout,
+        "fixed: ./a.txt\nfixed: ./a.utf16\n"
+    );
+    assert_eq!(std::fs::read(dir.join("img.png")).unwrap(), png);
+    assert_eq!(
+        std::fs::read(dir.join("a.utf16")).unwrap(),
+        b"\xFF\xFEa\0\n\0"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
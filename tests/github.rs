@@ -0,0 +1,36 @@
+mod common;
+use common::{run, temp_dir, write_files};
+
+/// `:` and newlines are not allowed in file names on Windows
+#[cfg(unix)]
+#[test]
+fn escaped_annotations() {
+    let dir = temp_dir("github");
+    write_files(
+        &dir,
+        &[
+            (
+                ".editorconfig",
+                "root = true\n[*]\ntrim_trailing_whitespace = true\nend_of_line = lf\n",
+            ),
+            ("a,b:c%d.txt", "a \r\n"),
+            ("clean.txt", "a\n"),
+            ("new\rline\n.txt", "a \n"),
+        ],
+    );
+
+    let output = run(&dir, &["check", "--format", "github", "."]);
+    assert!(!output.success);
+    assert_eq!(
+        output.stdout,
+        "::group::a,b:c%25d.txt\n\
+         ::error file=a%2Cb%3Ac%25d.txt,line=1,col=2,endColumn=3,title=trailing-white-spaces::trailing whitespace\n\
+         ::error file=a%2Cb%3Ac%25d.txt,line=1,col=3,endColumn=5,title=end-of-line-mismatch::expected lf line ending, found crlf\n\
+         ::endgroup::\n\
+         ::group::new%0Dline%0A.txt\n\
+         ::error file=new%0Dline%0A.txt,line=1,col=2,endColumn=3,title=trailing-white-spaces::trailing whitespace\n\
+         ::endgroup::\n\
+         ::notice title=editorconfig-lint::3 problem(s) in 2 file(s), 4 file(s) checked\n"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,67 @@
+use std::path::PathBuf;
+
+mod common;
+use common::{run, temp_dir, write_files};
+
+fn project(name: &str) -> PathBuf {
+    let dir = temp_dir(name);
+    write_files(
+        &dir,
+        &[
+            (
+                ".editorconfig",
+                "root = true\n[*.txt]\ntrim_trailing_whitespace = true\nindent_style = space\ninsert_final_newline = true\n",
+            ),
+            // wide characters in UTF-8 whose columns are bytes without `charset`
+            ("a.txt", "가나 \n\tx\nend"),
+        ],
+    );
+    dir
+}
+
+#[test]
+fn snippets() {
+    let dir = project("human");
+    let output = run(&dir, &["check", "--color", "never", "a.txt"]);
+    assert!(!output.success);
+    assert_eq!(
+        output.stdout,
+        "error[trailing-white-spaces]: trailing whitespace
+ --> a.txt:1:7
+  |
+1 | 가나·
+  |     ^
+  = note: .editorconfig expects `trim_trailing_whitespace = true`
+
+error[indent-style]: indentation should use space
+ --> a.txt:2:1
+  |
+2 | →x
+  | ^
+  = note: .editorconfig expects `indent_style = space`
+
+error[no-final-newline]: missing newline at end of file
+ --> a.txt:3:4
+  |
+3 | end
+  |    ^
+  = note: .editorconfig expects `insert_final_newline = true`
+
+3 problem(s) in 1 file(s), 1 file(s) checked
+"
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn color() {
+    let dir = project("human-color");
+    let output = run(&dir, &["check", "--color", "always", "a.txt"]);
+    assert!(output.stdout.starts_with(
+        "\x1b[1;31merror[trailing-white-spaces]\x1b[0m\x1b[1m: trailing whitespace\x1b[0m\n"
+    ));
+    assert!(output
+        .stdout
+        .ends_with("\x1b[1;33m3 problem(s) in 1 file(s), 1 file(s) checked\x1b[0m\n"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,48 @@
+use editorconfig_lint::{check, Config, Diagnosis, IndentSize, Reason};
+
+fn check_str(input: &str, indent_size: IndentSize, tab_width: Option<usize>) -> Vec<Diagnosis> {
+    let config = Config {
+        indent_size: Some(indent_size),
+        tab_width,
+        ..Default::default()
+    };
+    check(input.as_bytes(), config).unwrap()
+}
+
+/// Tabs are measured in columns up to the next tab stop
+#[test]
+fn tab_columns() {
+    assert_eq!(check_str("\t\tx\n", IndentSize::Columns(4), None), vec![]);
+    assert_eq!(check_str("  \tx\n", IndentSize::Columns(4), None), vec![]);
+    assert_eq!(check_str("\tx\n", IndentSize::Columns(4), Some(8)), vec![]);
+    assert_eq!(
+        check_str("\t  x\n", IndentSize::Columns(4), None),
+        vec![Diagnosis {
+            line: 1,
+            range: (1, 4),
+            reason: Reason::IndentSizeMismatch {
+                expected: 4,
+                actual: 6
+            },
+        }]
+    );
+}
+
+#[test]
+fn indent_size_tab() {
+    assert_eq!(
+        check_str("\tx\n        x\n", IndentSize::Tab, Some(8)),
+        vec![]
+    );
+    assert_eq!(
+        check_str("    x\n", IndentSize::Tab, Some(8)),
+        vec![Diagnosis {
+            line: 1,
+            range: (1, 5),
+            reason: Reason::IndentSizeMismatch {
+                expected: 8,
+                actual: 4
+            },
+        }]
+    );
+}
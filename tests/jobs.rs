@@ -0,0 +1,34 @@
+use std::path::Path;
+
+mod common;
+use common::{run, temp_dir};
+
+fn check(dir: &Path, jobs: &str) -> String {
+    run(dir, &["check", "--format", "text", "--jobs", jobs, "."]).stdout
+}
+
+#[test]
+fn output_does_not_depend_on_jobs() {
+    let dir = temp_dir("jobs");
+    std::fs::write(
+        dir.join(".editorconfig"),
+        "root = true\n[*]\ntrim_trailing_whitespace = true\nindent_style = space\n",
+    )
+    .unwrap();
+    for i in 0..20 {
+        let sub = dir.join(format!("dir{i}"));
+        std::fs::create_dir_all(&sub).unwrap();
+        for j in 0..20 {
+            // larger files take longer so that workers finish out of order
+            let content = format!("x\n{}\ty \n", "ok\n".repeat((i * 7 + j * 13) % 50 * 100));
+            std::fs::write(sub.join(format!("{j}.txt")), content).unwrap();
+        }
+    }
+
+    let sequential = check(&dir, "1");
+    assert_eq!(sequential.lines().count(), 20 * 20 * 2 + 1);
+    for _ in 0..3 {
+        assert_eq!(check(&dir, "8"), sequential);
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
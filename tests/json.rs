@@ -0,0 +1,89 @@
+//! The file which fails to be read is a dangling symlink
+#![cfg(unix)]
+
+use std::path::PathBuf;
+
+use serde_json::{json, Value};
+
+mod common;
+use common::{run, temp_dir, write_files};
+
+/// Directory with a file with problems and a file which fails to be read
+fn project(name: &str) -> PathBuf {
+    let dir = temp_dir(name);
+    write_files(
+        &dir,
+        &[
+            (
+                ".editorconfig",
+                "root = true\n[*]\nend_of_line = lf\ntrim_trailing_whitespace = true\n",
+            ),
+            ("a.txt", "a \r\n"),
+        ],
+    );
+    std::os::unix::fs::symlink("missing.txt", dir.join("b.txt")).unwrap();
+    dir
+}
+
+fn diagnostics() -> Value {
+    json!([
+        {
+            "file": "a.txt",
+            "line": 1,
+            "column": 2,
+            "end_column": 3,
+            "code": "trailing-white-spaces",
+            "message": "trailing whitespace",
+        },
+        {
+            "file": "a.txt",
+            "line": 1,
+            "column": 3,
+            "end_column": 5,
+            "code": "end-of-line-mismatch",
+            "expected": "lf",
+            "actual": "crlf",
+            "message": "expected lf line ending, found crlf",
+        },
+    ])
+}
+
+#[test]
+fn json() {
+    let dir = project("json");
+    let output = run(&dir, &["check", "--format", "json", "."]);
+    assert!(!output.success);
+    let mut document: Value = serde_json::from_str(&output.stdout).unwrap();
+
+    let errors = document["errors"].take();
+    assert_eq!(errors.as_array().unwrap().len(), 1);
+    assert_eq!(errors[0]["file"], "b.txt");
+    assert!(errors[0]["error"].is_string());
+    assert_eq!(
+        document,
+        json!({
+            "diagnostics": diagnostics(),
+            "errors": null,
+            "summary": { "checked_files": 2, "failed_files": 2, "problems": 2 },
+        })
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn json_lines() {
+    let dir = project("jsonl");
+    let output = run(&dir, &["check", "--format", "jsonl", "."]);
+    assert!(!output.success);
+    let mut lines: Vec<Value> = output
+        .stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    let error = lines.pop().unwrap();
+    assert_eq!(error["file"], "b.txt");
+    assert!(error["error"].is_string());
+    assert_eq!(Value::Array(lines), diagnostics());
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use editorconfig_lint::{Config, Diagnosis, JunitReporter, Reason, Reporter, Summary};
+
+#[test]
+fn escaped_report() {
+    let mut out = Vec::new();
+    let mut reporter = JunitReporter::new(&mut out);
+    let config = Config::default();
+    let diagnoses = [
+        Diagnosis {
+            line: 2,
+            range: (3, 4),
+            reason: Reason::TrailingWhiteSpaces,
+        },
+        Diagnosis {
+            line: 3,
+            range: (1, 1),
+            reason: Reason::NoFinalNewline,
+        },
+    ];
+    reporter
+        .report(Path::new("./a<&>.txt"), &config, &diagnoses)
+        .unwrap();
+    reporter
+        .report(Path::new("./clean.txt"), &config, &[])
+        .unwrap();
+    reporter
+        .report_error(Path::new("\"b'.txt"), &"bad <value> & \"more\"")
+        .unwrap();
+    reporter.finish(&Summary::default()).unwrap();
+
+    assert_eq!(
+        String::from_utf8(out).unwrap(),
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="editorconfig-lint" tests="3" failures="1" errors="1">
+  <testsuite name="editorconfig-lint" tests="3" failures="1" errors="1">
+    <testcase name="a&lt;&amp;&gt;.txt" classname="editorconfig-lint">
+      <failure type="trailing-white-spaces" message="trailing whitespace">a&lt;&amp;&gt;.txt:2:3: trailing whitespace</failure>
+      <failure type="no-final-newline" message="missing newline at end of file">a&lt;&amp;&gt;.txt:3:1: missing newline at end of file</failure>
+    </testcase>
+    <testcase name="clean.txt" classname="editorconfig-lint"/>
+    <testcase name="&quot;b&apos;.txt" classname="editorconfig-lint">
+      <error message="bad &lt;value&gt; &amp; &quot;more&quot;"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+    );
+}
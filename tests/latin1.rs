@@ -0,0 +1,34 @@
+use std::path::{Path, PathBuf};
+
+use editorconfig_lint::{check, Config, Diagnosis, Reason};
+
+fn check_corpus(name: &str) -> Vec<Diagnosis> {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/corpus/latin1")
+        .join(name);
+    let config = Config::get_config_for(Path::new(&path)).unwrap();
+    check(&std::fs::read(&path).unwrap()[..], config).unwrap()
+}
+
+#[test]
+fn valid_latin1() {
+    assert_eq!(check_corpus("valid.txt"), vec![]);
+}
+
+#[test]
+fn control_characters() {
+    let control = |line, col, code_point| Diagnosis {
+        line,
+        range: (col, col + 1),
+        reason: Reason::ControlCharacter { code_point },
+    };
+    assert_eq!(
+        check_corpus("control.txt"),
+        vec![
+            control(1, 2, 0x85),
+            control(2, 1, 0x7F),
+            control(3, 3, 0x9F),
+            control(3, 5, 0xA0),
+        ]
+    );
+}
@@ -0,0 +1,190 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    process::{Child, ChildStdout, Command, Stdio},
+};
+
+use serde_json::{json, Value};
+
+mod common;
+use common::temp_dir;
+
+struct Client {
+    child: Child,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl Client {
+    fn start() -> Client {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_editorconfig-lint"))
+            .arg("lsp")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let stdout = BufReader::new(child.stdout.take().unwrap());
+        Client { child, stdout }
+    }
+
+    fn send(&mut self, message: Value) {
+        let body = message.to_string();
+        let stdin = self.child.stdin.as_mut().unwrap();
+        write!(stdin, "Content-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+        stdin.flush().unwrap();
+    }
+
+    fn receive(&mut self) -> Value {
+        let mut len = 0;
+        loop {
+            let mut header = String::new();
+            self.stdout.read_line(&mut header).unwrap();
+            let header = header.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some(value) = header.strip_prefix("Content-Length: ") {
+                len = value.parse().unwrap();
+            }
+        }
+        let mut body = vec![0; len];
+        self.stdout.read_exact(&mut body).unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn request(&mut self, id: u64, method: &str, params: Value) -> Value {
+        self.send(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}));
+        loop {
+            let message = self.receive();
+            if message["id"] == id {
+                return message["result"].clone();
+            }
+        }
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({"jsonrpc": "2.0", "method": method, "params": params}));
+    }
+
+    fn diagnostics(&mut self) -> Value {
+        loop {
+            let message = self.receive();
+            if message["method"] == "textDocument/publishDiagnostics" {
+                return message["params"]["diagnostics"].clone();
+            }
+        }
+    }
+}
+
+/// `[start line, start character, end line, end character]`
+fn range(range: &Value) -> [u64; 4] {
+    let position = |p: &Value| {
+        (
+            p["line"].as_u64().unwrap(),
+            p["character"].as_u64().unwrap(),
+        )
+    };
+    let (l1, c1) = position(&range["start"]);
+    let (l2, c2) = position(&range["end"]);
+    [l1, c1, l2, c2]
+}
+
+fn ranges(diagnostics: &Value) -> Vec<(String, [u64; 4])> {
+    diagnostics
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| (d["code"].as_str().unwrap().to_string(), range(&d["range"])))
+        .collect()
+}
+
+#[test]
+fn diagnostics_code_actions_and_formatting() {
+    let dir = temp_dir("lsp");
+    std::fs::write(
+        dir.join(".editorconfig"),
+        "root = true\n[*.rs]\nindent_style = space\nindent_size = 4\nend_of_line = lf\ntrim_trailing_whitespace = true\ninsert_final_newline = true\n",
+    )
+    .unwrap();
+    // the document does not need to exist
+    let uri = lsp_types::Url::from_file_path(dir.join("a.rs")).unwrap();
+    let text = "fn a() {  \r\n\tx;\n}";
+
+    let mut client = Client::start();
+    client.request(1, "initialize", json!({"capabilities": {}}));
+    client.notify("initialized", json!({}));
+    client.notify(
+        "textDocument/didOpen",
+        json!({"textDocument": {"uri": uri, "languageId": "rust", "version": 1, "text": text}}),
+    );
+    assert_eq!(
+        ranges(&client.diagnostics()),
+        [
+            ("trailing-white-spaces".to_string(), [0, 8, 0, 10]),
+            ("end-of-line-mismatch".to_string(), [0, 10, 1, 0]),
+            ("indent-style".to_string(), [1, 0, 1, 1]),
+            ("no-final-newline".to_string(), [2, 1, 2, 1]),
+        ]
+    );
+
+    let actions = client.request(
+        2,
+        "textDocument/codeAction",
+        json!({
+            "textDocument": {"uri": uri},
+            "range": {"start": {"line": 0, "character": 0}, "end": {"line": 0, "character": 0}},
+            "context": {"diagnostics": []},
+        }),
+    );
+    let edits: Vec<_> = actions
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|action| {
+            let edit = &action["edit"]["changes"][uri.as_str()][0];
+            (
+                action["title"].as_str().unwrap().to_string(),
+                range(&edit["range"]),
+                edit["newText"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        edits,
+        [
+            (
+                "Trim trailing whitespace".to_string(),
+                [0, 8, 0, 10],
+                "".to_string()
+            ),
+            (
+                "Convert line ending to lf".to_string(),
+                [0, 10, 1, 0],
+                "\n".to_string()
+            ),
+        ]
+    );
+
+    let edits = client.request(
+        3,
+        "textDocument/formatting",
+        json!({"textDocument": {"uri": uri}, "options": {"tabSize": 4, "insertSpaces": true}}),
+    );
+    assert_eq!(range(&edits[0]["range"]), [0, 8, 2, 1]);
+    assert_eq!(edits[0]["newText"], "\n    x;\n}\n");
+
+    // config changes are picked up
+    std::fs::write(
+        dir.join(".editorconfig"),
+        "root = true\n[*.rs]\nindent_style = tab\n",
+    )
+    .unwrap();
+    client.notify(
+        "workspace/didChangeWatchedFiles",
+        json!({"changes": [{"uri": lsp_types::Url::from_file_path(dir.join(".editorconfig")).unwrap(), "type": 2}]}),
+    );
+    assert_eq!(ranges(&client.diagnostics()), []);
+
+    client.request(4, "shutdown", Value::Null);
+    client.notify("exit", Value::Null);
+    assert!(client.child.wait().unwrap().success());
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,68 @@
+use editorconfig_lint::{check, Charset, Config, Diagnosis, Reason};
+
+fn check_str(input: &str, charset: Option<Charset>) -> Vec<Diagnosis> {
+    let config = Config {
+        max_line_length: Some(8),
+        tab_width: Some(4),
+        charset,
+        ..Default::default()
+    };
+    check(input.as_bytes(), config).unwrap()
+}
+
+fn too_long(line: usize, range: (usize, usize), actual: usize) -> Diagnosis {
+    Diagnosis {
+        line,
+        range,
+        reason: Reason::LineTooLong {
+            expected: 8,
+            actual,
+        },
+    }
+}
+
+#[test]
+fn ascii() {
+    for charset in [None, Some(Charset::Utf8), Some(Charset::Latin1)] {
+        assert_eq!(check_str("12345678\n", charset), vec![]);
+        assert_eq!(
+            check_str("12345678\n123456789012\r\n1", charset),
+            vec![too_long(2, (9, 13), 12)]
+        );
+        assert_eq!(
+            check_str("1234 6789", charset),
+            vec![too_long(1, (9, 10), 9)]
+        );
+    }
+}
+
+#[test]
+fn tabs() {
+    assert_eq!(check_str("\t\tab\n", None), vec![too_long(1, (3, 5), 10)]);
+    assert_eq!(check_str("ab\tcd\n", None), vec![]);
+    assert_eq!(check_str("abcde\tx\n", None), vec![too_long(1, (7, 8), 9)]);
+}
+
+#[test]
+fn wide_characters() {
+    assert_eq!(check_str("가나다라\n", Some(Charset::Utf8)), vec![]);
+    assert_eq!(
+        check_str("가나다라a\n", Some(Charset::Utf8)),
+        vec![too_long(1, (5, 6), 9)]
+    );
+    // columns are bytes with unchecked encoding
+    assert_eq!(check_str("가나다라\n", None), vec![]);
+    assert_eq!(
+        check_str("가나다라마\n", None),
+        vec![too_long(1, (15, 16), 10)]
+    );
+}
+
+#[test]
+fn off() {
+    let config = Config {
+        max_line_length: None,
+        ..Default::default()
+    };
+    assert_eq!(check("x".repeat(1000).as_bytes(), config).unwrap(), vec![]);
+}
@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use editorconfig_lint::Pattern;
+
+fn check(section: &str, matched: &[&str], unmatched: &[&str]) {
+    let pattern = Pattern::new(section);
+    for path in matched {
+        assert!(pattern.matches_path(Path::new(path)), "[{section}] {path}");
+    }
+    for path in unmatched {
+        assert!(!pattern.matches_path(Path::new(path)), "[{section}] {path}");
+    }
+}
+
+#[test]
+fn star() {
+    check(
+        "a*e.c",
+        &["ace.c", "abcde.c", "x/ae.c"],
+        &["a/e.c", "ae.cc"],
+    );
+    check(
+        "Bar/*",
+        &["Bar/foo.txt"],
+        &["Bar/foo/bar.txt", "x/Bar/foo.txt"],
+    );
+    check("*", &["a", "a/b/c"], &[]);
+    check("a/**/b", &["a/b", "a/x/b", "a/x/y/b"], &["ab", "x/a/b"]);
+    check("a**z.c", &["az.c", "a/z.c", "a/b/c/z.c"], &["z.c"]);
+    check("**/z.c", &["z.c", "a/z.c"], &["z.cc"]);
+}
+
+#[test]
+fn question() {
+    check("som?.c", &["some.c"], &["som.c", "som/.c", "somee.c"]);
+}
+
+#[test]
+fn brackets() {
+    check("[ab].a", &["a.a", "b.a"], &["c.a"]);
+    check("[!ab].b", &["c.b"], &["a.b", "/.b"]);
+    check("[d-g].c", &["d.c", "f.c", "g.c"], &["h.c"]);
+    check("[-ab].f", &["-.f", "a.f"], &["c.f"]);
+    check("[\\]ab].g", &["].g", "a.g"], &["c.g"]);
+    check("ab[e/]cd.i", &["ab[e/]cd.i"], &["abecd.i", "ab/cd.i"]);
+    check("ab[/c", &["ab[/c"], &["abc"]);
+    check("[ab", &["[ab"], &["a"]);
+}
+
+#[test]
+fn braces() {
+    check(
+        "*.{py,js,html}",
+        &["a.py", "a/b.js", "a.html"],
+        &["a.css", "a.{py,js,html}"],
+    );
+    check("{single}.b", &["{single}.b"], &["single.b"]);
+    check("{}.c", &["{}.c"], &[".c"]);
+    check("a{b,c,}.d", &["a.d", "ab.d", "ac.d"], &["a,.d"]);
+    check("{.f", &["{.f"], &[".f"]);
+    check(
+        "{word,{also},this}.g",
+        &["word.g", "{also}.g", "this.g"],
+        &["also.g"],
+    );
+    check("{},b}.h", &["{},b}.h"], &["b.h"]);
+    check("{a,b{c,d}}.j", &["a.j", "bc.j", "bd.j"], &["b.j"]);
+    check("\\{a,b\\}.k", &["{a,b}.k"], &["a.k"]);
+    check("{a,b,c", &["{a,b,c"], &["a"]);
+}
+
+#[test]
+fn number_range() {
+    check(
+        "{3..120}",
+        &["3", "15", "60", "120"],
+        &["1", "5a", "121", "060"],
+    );
+    check("a{-5..+5}", &["a-5", "a0", "a5", "a+5"], &["a6", "a-6"]);
+    check("{aardvark..antelope}", &["{aardvark..antelope}"], &["a"]);
+    check("{1..3}{1..3}", &["11", "23", "33"], &["4", "34", "111"]);
+}
+
+#[test]
+fn escape() {
+    check("\\*.a", &["*.a"], &["x.a"]);
+    check("a\\?.b", &["a?.b"], &["ax.b"]);
+    check("\\[ab\\].c", &["[ab].c"], &["a.c"]);
+}
+
+#[test]
+fn anchored() {
+    check("a/b.c", &["a/b.c"], &["x/a/b.c"]);
+    check("/b.c", &["b.c"], &["x/b.c"]);
+    check("b.c", &["b.c", "x/b.c", "x/y/b.c"], &["xb.c"]);
+}
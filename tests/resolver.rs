@@ -0,0 +1,158 @@
+use std::path::PathBuf;
+
+use editorconfig_lint::{Config, ConfigResolver, IndentSize, IndentStyle};
+
+mod common;
+use common::temp_dir;
+
+/// Temporary directory with an empty `sub` directory
+fn project(name: &str) -> PathBuf {
+    let dir = temp_dir(name);
+    std::fs::create_dir(dir.join("sub")).unwrap();
+    dir
+}
+
+#[test]
+fn same_as_uncached() {
+    let dir = project("resolver-same");
+    std::fs::write(
+        dir.join(".editorconfig"),
+        "root = true\n[*]\nindent_style = tab\n[*.{rs,toml}]\nindent_size = 4\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("sub/.editorconfig"),
+        "[*.rs]\nindent_style = space\n",
+    )
+    .unwrap();
+
+    let resolver = ConfigResolver::new();
+    for name in ["a.rs", "sub/a.rs", "sub/a.toml", "sub/a.txt"] {
+        let path = dir.join(name);
+        std::fs::write(&path, "").unwrap();
+        for _ in 0..2 {
+            assert_eq!(
+                resolver.get_config_for(&path).unwrap(),
+                Config::get_config_for(&path).unwrap(),
+                "{name}"
+            );
+        }
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn reparse_modified() {
+    let dir = project("resolver-modified");
+    let path = dir.join("a.txt");
+    std::fs::write(&path, "").unwrap();
+    std::fs::write(
+        dir.join(".editorconfig"),
+        "root = true\n[*]\nindent_style = tab\n",
+    )
+    .unwrap();
+
+    let resolver = ConfigResolver::new();
+    let config = resolver.get_config_for(&path).unwrap();
+    assert_eq!(config.indent_style, Some(IndentStyle::Tab));
+
+    std::fs::write(
+        dir.join(".editorconfig"),
+        "root = true\n[*]\nindent_style = space\n",
+    )
+    .unwrap();
+    let config = resolver.get_config_for(&path).unwrap();
+    assert_eq!(config.indent_style, Some(IndentStyle::Space));
+
+    // nearer config file is created
+    std::fs::write(dir.join("sub/.editorconfig"), "[*]\nindent_style = tab\n").unwrap();
+    let path = dir.join("sub/a.txt");
+    std::fs::write(&path, "").unwrap();
+    let config = resolver.get_config_for(&path).unwrap();
+    assert_eq!(config.indent_style, Some(IndentStyle::Tab));
+
+    std::fs::remove_file(dir.join("sub/.editorconfig")).unwrap();
+    let config = resolver.get_config_for(&path).unwrap();
+    assert_eq!(config.indent_style, Some(IndentStyle::Space));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn non_existent_path() {
+    let dir = project("resolver-non-existent");
+    std::fs::write(
+        dir.join(".editorconfig"),
+        "root = true\n[*]\nindent_style = tab\n[missing/*.rs]\nindent_style = space\n",
+    )
+    .unwrap();
+
+    let resolver = ConfigResolver::new();
+    let config = resolver.get_config_for(&dir.join("a.rs")).unwrap();
+    assert_eq!(config.indent_style, Some(IndentStyle::Tab));
+    let config = resolver.get_config_for(&dir.join("missing/a.rs")).unwrap();
+    assert_eq!(config.indent_style, Some(IndentStyle::Space));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn symlinks() {
+    use editorconfig_lint::PathResolution;
+
+    let dir = project("resolver-symlinks");
+    std::fs::write(
+        dir.join(".editorconfig"),
+        "root = true\n[*]\nindent_style = tab\n[sub/**]\nindent_style = space\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("sub/target.txt"), "").unwrap();
+    std::os::unix::fs::symlink(dir.join("sub/target.txt"), dir.join("link.txt")).unwrap();
+
+    let link = dir.join("link.txt");
+    let style = |resolution| {
+        Config::get_config_for_with(&link, resolution)
+            .unwrap()
+            .indent_style
+    };
+    assert_eq!(style(PathResolution::Canonical), Some(IndentStyle::Space));
+    assert_eq!(
+        style(PathResolution::Lexical {
+            follow_symlinks: true
+        }),
+        Some(IndentStyle::Space)
+    );
+    assert_eq!(
+        style(PathResolution::Lexical {
+            follow_symlinks: false
+        }),
+        Some(IndentStyle::Tab)
+    );
+
+    // `..` is removed before the symlink is looked up
+    let path = dir.join("sub/../missing/../link.txt");
+    let resolver = ConfigResolver::with_path_resolution(PathResolution::Lexical {
+        follow_symlinks: false,
+    });
+    let config = resolver.get_config_for(&path).unwrap();
+    assert_eq!(config.indent_style, Some(IndentStyle::Tab));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn invalid_value_is_ignored() {
+    let dir = project("resolver-invalid");
+    std::fs::write(
+        dir.join(".editorconfig"),
+        "root = true\n[*]\nindent_style = tab\n[*.md]\nindent_size = two\n[*.txt]\nindent_size = 2x\ntab_width = 8\n",
+    )
+    .unwrap();
+
+    let resolver = ConfigResolver::new();
+    let config = resolver.get_config_for(&dir.join("a.txt")).unwrap();
+    assert_eq!(config.indent_style, Some(IndentStyle::Tab));
+    assert_eq!(config.indent_size, Some(IndentSize::Columns(8)));
+    assert_eq!(config.tab_width, Some(8));
+    let config = resolver.get_config_for(&dir.join("a.md")).unwrap();
+    assert_eq!(config.indent_size, Some(IndentSize::Tab));
+    std::fs::remove_dir_all(dir).unwrap();
+}
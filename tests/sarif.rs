@@ -0,0 +1,116 @@
+use editorconfig_lint::RULES;
+use serde_json::{json, Value};
+
+mod common;
+use common::{run, temp_dir, write_files};
+
+const CONFIG: &str = "root = true\n[*]\ntrim_trailing_whitespace = true\n";
+
+#[cfg(unix)]
+#[test]
+fn uris_are_relative_to_current_directory() {
+    let dir = temp_dir("sarif-uri");
+    write_files(
+        &dir,
+        &[
+            ("proj/.editorconfig", CONFIG),
+            ("proj/sub/a b.txt", "a \n"),
+            ("other/.editorconfig", CONFIG),
+            ("other/c.txt", "c \n"),
+        ],
+    );
+
+    let output = run(
+        &dir.join("proj"),
+        &["check", "--format", "sarif", "./sub", "../other/c.txt"],
+    );
+    let log: Value = serde_json::from_str(&output.stdout).unwrap();
+    let run = &log["runs"][0];
+    assert_eq!(
+        run["originalUriBaseIds"],
+        json!({ "%SRCROOT%": { "uri": format!("file://{}/proj/", dir.display()) } })
+    );
+    let locations: Vec<_> = run["results"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|result| &result["locations"][0]["physicalLocation"]["artifactLocation"])
+        .collect();
+    assert_eq!(
+        locations,
+        [
+            &json!({ "uri": "sub/a%20b.txt", "uriBaseId": "%SRCROOT%" }),
+            &json!({ "uri": format!("file://{}/other/c.txt", dir.display()) }),
+        ]
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn log_structure() {
+    let dir = temp_dir("sarif-log");
+    write_files(
+        &dir,
+        &[
+            (
+                ".editorconfig",
+                "root = true\n[*]\ntrim_trailing_whitespace = true\n[*.txt]\nmax_line_length = 4\n",
+            ),
+            ("a.txt", "ok\nlong line \n"),
+        ],
+    );
+    std::os::unix::fs::symlink("missing.txt", dir.join("b.txt")).unwrap();
+
+    let output = run(&dir, &["check", "--format", "sarif", "."]);
+    assert!(!output.success);
+    let log: Value = serde_json::from_str(&output.stdout).unwrap();
+    assert_eq!(log["version"], "2.1.0");
+    let run = &log["runs"][0];
+
+    let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+    assert_eq!(
+        rules.iter().map(|rule| &rule["id"]).collect::<Vec<_>>(),
+        RULES.iter().map(|rule| rule.code).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        rules[3]["properties"]["editorconfig-property"],
+        "trim_trailing_whitespace"
+    );
+
+    let results = run["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    for result in results {
+        let index = result["ruleIndex"].as_u64().unwrap() as usize;
+        assert_eq!(rules[index]["id"], result["ruleId"]);
+    }
+    assert_eq!(results[0]["ruleId"], "trailing-white-spaces");
+    assert_eq!(
+        results[0]["locations"][0]["physicalLocation"]["region"],
+        json!({ "startLine": 2, "startColumn": 10, "endColumn": 11 })
+    );
+    assert_eq!(
+        results[0]["properties"]["editorconfig"],
+        json!({ "property": "trim_trailing_whitespace", "value": "true" })
+    );
+    assert_eq!(results[1]["ruleId"], "line-too-long");
+    assert_eq!(
+        results[1]["locations"][0]["physicalLocation"]["region"],
+        json!({ "startLine": 2, "startColumn": 5, "endColumn": 11 })
+    );
+    assert_eq!(
+        results[1]["properties"]["editorconfig"],
+        json!({ "property": "max_line_length", "value": "4" })
+    );
+
+    let invocation = &run["invocations"][0];
+    assert_eq!(invocation["executionSuccessful"], false);
+    let notifications = invocation["toolExecutionNotifications"].as_array().unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0]["level"], "error");
+    assert_eq!(
+        notifications[0]["locations"][0]["physicalLocation"]["artifactLocation"],
+        json!({ "uri": "b.txt", "uriBaseId": "%SRCROOT%" })
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,78 @@
+use editorconfig_lint::{check, Charset, Config, Diagnosis, Reason};
+
+fn encode(s: &str, big_endian: bool) -> Vec<u8> {
+    s.encode_utf16()
+        .flat_map(|unit| {
+            if big_endian {
+                unit.to_be_bytes()
+            } else {
+                unit.to_le_bytes()
+            }
+        })
+        .collect()
+}
+
+fn check_utf16(input: &[u8], charset: Charset) -> Vec<Diagnosis> {
+    let config = Config {
+        charset: Some(charset),
+        ..Default::default()
+    };
+    check(input, config).unwrap()
+}
+
+fn invalid(line: usize, col: usize) -> Diagnosis {
+    Diagnosis {
+        line,
+        range: (col, col + 1),
+        reason: Reason::InvalidCharacter,
+    }
+}
+
+#[test]
+fn valid_utf16() {
+    for big_endian in [false, true] {
+        let charset = if big_endian {
+            Charset::Utf16BigEndian
+        } else {
+            Charset::Utf16LittleEndian
+        };
+        let input = encode("\u{FEFF}\u{0D00}\u{0A00}\t가\r\n😀 \u{FFFD}\n", big_endian);
+        assert_eq!(check_utf16(&input, charset), vec![]);
+    }
+}
+
+#[test]
+fn lone_surrogates() {
+    // high surrogate followed by a normal character, lone low surrogate, high surrogate at EOF
+    let units: [u16; 5] = [0xD83D, b'a' as u16, 0xDE00, b'b' as u16, 0xD83D];
+    let input = units
+        .iter()
+        .flat_map(|unit| unit.to_le_bytes())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        check_utf16(&input, Charset::Utf16LittleEndian),
+        vec![invalid(1, 1), invalid(1, 3), invalid(1, 5)]
+    );
+
+    // U+E000 follows high surrogate instead of low surrogate
+    let input = [0xD8, 0x3D, 0xDC, 0x00];
+    assert_eq!(check_utf16(&input, Charset::Utf16BigEndian), vec![]);
+    let input = [0xD8, 0x3D, 0xE0, 0x00];
+    assert_eq!(
+        check_utf16(&input, Charset::Utf16BigEndian),
+        vec![invalid(1, 1)]
+    );
+}
+
+#[test]
+fn bom_mismatch() {
+    let input = encode("\u{FEFF}a\n", false);
+    assert_eq!(
+        check_utf16(&input, Charset::Utf16BigEndian),
+        vec![Diagnosis {
+            line: 1,
+            range: (1, 1),
+            reason: Reason::BomMismatch,
+        }]
+    );
+}
@@ -0,0 +1,88 @@
+use std::path::Path;
+
+mod common;
+use common::{git, run, temp_dir, write_files};
+
+fn checked_files(dir: &Path, args: &[&str]) -> Vec<String> {
+    let output = run(dir, &[&["check", "--format", "text"], args].concat());
+    let mut files: Vec<_> = output
+        .stdout
+        .lines()
+        .filter_map(|line| line.split_once(" at "))
+        .map(|(_, location)| location.split(':').next().unwrap().to_string())
+        .collect();
+    files.dedup();
+    files
+}
+
+#[test]
+fn globs_are_relative_to_walk_root() {
+    let dir = temp_dir("walk-globs");
+    let project = dir.join("proj");
+    write_files(
+        &project,
+        &[
+            (
+                ".editorconfig",
+                "root = true\n[*]\ntrim_trailing_whitespace = true\n",
+            ),
+            ("a.txt", "x \n"),
+            ("sub/a.txt", "x \n"),
+            ("sub/b.txt", "x \n"),
+        ],
+    );
+
+    let root = project.to_str().unwrap();
+    let file = |name: &str| project.join(name).to_str().unwrap().to_string();
+    assert_eq!(
+        checked_files(&dir, &[root, "--include", "sub/*.txt"]),
+        [file("sub/a.txt"), file("sub/b.txt")]
+    );
+    assert_eq!(
+        checked_files(&dir, &[root, "--exclude", "sub/a.txt"]),
+        [file("a.txt"), file("sub/b.txt")]
+    );
+    assert_eq!(
+        checked_files(&project, &[".", "--exclude", "sub/a.txt"]),
+        ["./a.txt", "./sub/b.txt"]
+    );
+    assert_eq!(
+        checked_files(&dir, &["proj/sub", "--include", "b.txt"]),
+        ["proj/sub/b.txt"]
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn ignore_files() {
+    let dir = temp_dir("walk-ignore");
+    git(&dir, &["init", "-q"]);
+    write_files(
+        &dir,
+        &[
+            (
+                ".editorconfig",
+                "root = true\n[*]\ntrim_trailing_whitespace = true\n",
+            ),
+            (".gitignore", "gitignored.txt\n"),
+            (".git/info/exclude", "excluded.txt\n"),
+            (".editorconfig-lintignore", "lintignored.txt\n"),
+            ("excluded.txt", "x \n"),
+            ("gitignored.txt", "x \n"),
+            ("kept.txt", "x \n"),
+            ("lintignored.txt", "x \n"),
+        ],
+    );
+
+    assert_eq!(checked_files(&dir, &["."]), ["./kept.txt"]);
+    assert_eq!(
+        checked_files(&dir, &[".", "--no-ignore"]),
+        [
+            "./excluded.txt",
+            "./gitignored.txt",
+            "./kept.txt",
+            "./lintignored.txt"
+        ]
+    );
+    std::fs::remove_dir_all(dir).unwrap();
+}